@@ -1,17 +1,147 @@
-use std::{num::ParseIntError, str::FromStr};
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    io::{self, BufRead},
+    num::ParseIntError,
+    str::FromStr,
+};
 use thiserror::Error;
 
 pub fn part_1(Input(food): Input) -> Calories {
-    food.iter()
-        .map(|items| items.iter().sum())
-        .max()
-        .unwrap_or(0)
+    top_k(&food, 1).iter().map(|elf| elf.calories).sum()
 }
 
 pub fn part_2(Input(food): Input) -> Calories {
-    let mut calories: Vec<Calories> = food.iter().map(|items| items.iter().sum()).collect();
-    calories.sort();
-    calories.into_iter().rev().take(3).sum()
+    top_k(&food, 3).iter().map(|elf| elf.calories).sum()
+}
+
+/// The `k` elves carrying the most calories, most first and the earlier
+/// elf first on a tie.
+pub fn top_k(food: &[Items], k: usize) -> Vec<Elf> {
+    let mut top = TopK::new(k);
+
+    for (idx, items) in food.iter().enumerate() {
+        top.push(Elf {
+            idx,
+            calories: items.iter().sum(),
+        });
+    }
+
+    top.into_elves()
+}
+
+/// Like [`top_k`], reading the elves from `reader` one line at a time.
+pub fn read_top_k(reader: impl BufRead, k: usize) -> Result<Vec<Elf>, ReadError> {
+    let mut top = TopK::new(k);
+    let mut current: Option<Elf> = None;
+
+    parse_items(
+        reader.lines().map(|line| line.map_err(ReadError::from)),
+        |idx, calories| match &mut current {
+            Some(elf) if elf.idx == idx => elf.calories += calories,
+            _ => {
+                if let Some(elf) = current.replace(Elf { idx, calories }) {
+                    top.push(elf);
+                }
+            }
+        },
+    )?;
+
+    if let Some(elf) = current {
+        top.push(elf);
+    }
+
+    Ok(top.into_elves())
+}
+
+/// Feeds each item to `add` along with the index of the elf carrying it.
+///
+/// Elves are separated by a single blank line and carry at least one item, so
+/// a run of blank lines, or one at either end of the input, is an error.
+/// [`Input::from_str`] and [`read_top_k`] both go through here so that they
+/// agree on which elf is which.
+fn parse_items<L, E>(
+    lines: impl IntoIterator<Item = Result<L, E>>,
+    mut add: impl FnMut(usize, Calories),
+) -> Result<(), E>
+where
+    L: AsRef<str>,
+    E: From<InputError>,
+{
+    let mut elf_idx = 0;
+    let mut item_idx = 0;
+
+    for line in lines {
+        let line = line?;
+        let line = line.as_ref();
+
+        if line.is_empty() {
+            if item_idx == 0 {
+                return Err(InputError::EmptyElf { elf_idx }.into());
+            }
+
+            elf_idx += 1;
+            item_idx = 0;
+            continue;
+        }
+
+        let calories = line.parse().map_err(|err| InputError::InvalidCalories {
+            elf_idx,
+            item_idx,
+            err,
+        })?;
+
+        add(elf_idx, calories);
+        item_idx += 1;
+    }
+
+    if item_idx == 0 && elf_idx > 0 {
+        return Err(InputError::EmptyElf { elf_idx }.into());
+    }
+
+    Ok(())
+}
+
+/// Keeps the best `k` elves seen so far in a min-heap, so each elf costs
+/// `O(log k)` and memory stays at `k` elves.
+struct TopK {
+    k: usize,
+    heap: BinaryHeap<Reverse<(Calories, Reverse<usize>)>>,
+}
+
+impl TopK {
+    fn new(k: usize) -> Self {
+        Self {
+            k,
+            heap: BinaryHeap::with_capacity(k),
+        }
+    }
+
+    fn push(&mut self, elf: Elf) {
+        let key = (elf.calories, Reverse(elf.idx));
+
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(key));
+        } else if let Some(mut worst) = self.heap.peek_mut() {
+            if key > worst.0 {
+                *worst = Reverse(key);
+            }
+        }
+    }
+
+    fn into_elves(self) -> Vec<Elf> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((calories, Reverse(idx)))| Elf { idx, calories })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Elf {
+    pub idx: usize,
+    pub calories: Calories,
 }
 
 type Calories = u32;
@@ -22,7 +152,7 @@ type Food = Vec<Items>;
 
 pub struct Input(Food);
 
-#[derive(Debug, Error)]
+#[derive(Debug, Error, PartialEq)]
 pub enum InputError {
     #[error("invalid calories for elf {elf_idx}, item {item_idx}: {err}")]
     InvalidCalories {
@@ -30,29 +160,33 @@ pub enum InputError {
         item_idx: usize,
         err: ParseIntError,
     },
+    #[error("elf {elf_idx} carries no items")]
+    EmptyElf { elf_idx: usize },
+}
+
+#[derive(Debug, Error)]
+pub enum ReadError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Input(#[from] InputError),
 }
 
 impl FromStr for Input {
     type Err = InputError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        s.split("\n\n")
-            .enumerate()
-            .map(|(elf_idx, items)| {
-                items
-                    .lines()
-                    .enumerate()
-                    .map(|(item_idx, item)| {
-                        item.parse().map_err(|err| InputError::InvalidCalories {
-                            elf_idx,
-                            item_idx,
-                            err,
-                        })
-                    })
-                    .collect::<Result<Items, _>>()
-            })
-            .collect::<Result<Food, _>>()
-            .map(Self)
+        let mut food = Food::new();
+
+        parse_items(s.lines().map(Ok), |elf_idx, calories| {
+            if elf_idx == food.len() {
+                food.push(Items::new());
+            }
+
+            food[elf_idx].push(calories);
+        })?;
+
+        Ok(Self(food))
     }
 }
 
@@ -90,4 +224,66 @@ mod tests {
 
         assert_eq!(output, 45000);
     }
+
+    #[test]
+    fn test_top_k() {
+        let Input(food) = INPUT.parse().unwrap();
+        let elf = |idx, calories| Elf { idx, calories };
+
+        assert_eq!(
+            top_k(&food, 3),
+            [elf(3, 24000), elf(2, 11000), elf(4, 10000)]
+        );
+        assert_eq!(top_k(&food, 0), []);
+        assert_eq!(top_k(&food, 9).len(), 5);
+        assert_eq!(
+            top_k(&[vec![5], vec![7], vec![2, 3], vec![7]], 3),
+            [elf(1, 7), elf(3, 7), elf(0, 5)]
+        );
+    }
+
+    #[test]
+    fn test_read_top_k() {
+        let Input(food) = INPUT.parse().unwrap();
+
+        for k in 0..=6 {
+            assert_eq!(read_top_k(INPUT.as_bytes(), k).unwrap(), top_k(&food, k));
+        }
+
+        assert!(matches!(
+            read_top_k("1000\n\n20x0\n".as_bytes(), 1),
+            Err(ReadError::Input(InputError::InvalidCalories {
+                elf_idx: 1,
+                item_idx: 0,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_read_top_k_agrees_with_from_str() {
+        let elf = |idx, calories| Elf { idx, calories };
+        let from_str = |s: &str| s.parse().map(|Input(food)| top_k(&food, 9));
+        let read = |s: &str| {
+            read_top_k(s.as_bytes(), 9).map_err(|err| match err {
+                ReadError::Input(err) => err,
+                ReadError::Io(err) => panic!("{err}"),
+            })
+        };
+
+        for (input, expected) in [
+            ("", Ok(vec![])),
+            ("1", Ok(vec![elf(0, 1)])),
+            ("1\n\n2\n", Ok(vec![elf(1, 2), elf(0, 1)])),
+            ("1\r\n\r\n2\r\n", Ok(vec![elf(1, 2), elf(0, 1)])),
+            ("1\n\n\n2\n", Err(InputError::EmptyElf { elf_idx: 1 })),
+            ("1\n\n", Err(InputError::EmptyElf { elf_idx: 1 })),
+            ("1\n\n\n", Err(InputError::EmptyElf { elf_idx: 1 })),
+            ("\n1\n", Err(InputError::EmptyElf { elf_idx: 0 })),
+            ("\n", Err(InputError::EmptyElf { elf_idx: 0 })),
+        ] {
+            assert_eq!(from_str(input), expected, "{input:?}");
+            assert_eq!(read(input), expected, "{input:?}");
+        }
+    }
 }
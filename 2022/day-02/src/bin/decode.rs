@@ -0,0 +1,13 @@
+use color_eyre::Result;
+use day_02::Input;
+use std::fs;
+
+fn main() -> Result<()> {
+    let input: Input = fs::read_to_string("./input.txt")?.parse()?;
+
+    for (interpretation, score) in input.evaluate() {
+        println!("{interpretation}: {score}");
+    }
+
+    Ok(())
+}
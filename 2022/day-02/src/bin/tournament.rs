@@ -0,0 +1,23 @@
+use day_02::{BeatLast, Constant, Cycle, Entrant, Game, Random, Shape, Tournament};
+
+fn main() {
+    let game = Game::rock_paper_scissors_lizard_spock();
+    let mut entrants = vec![
+        Entrant::new("cycle", Cycle),
+        Entrant::new("beat last", BeatLast),
+        Entrant::new("random", Random::new(2022)),
+    ];
+
+    entrants.extend(
+        game.shapes()
+            .map(|shape: Shape| Entrant::new(game.name(shape).to_lowercase(), Constant(shape))),
+    );
+
+    let standings = Tournament {
+        game: &game,
+        rounds: 1_000,
+    }
+    .run(&mut entrants);
+
+    print!("{standings}");
+}
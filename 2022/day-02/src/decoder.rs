@@ -0,0 +1,215 @@
+use crate::{game::Outcome, HandShape, Input, Round, SecondEncoding};
+use std::fmt;
+
+/// One interpretation of the second column of the strategy guide.
+pub trait Decoder: fmt::Display {
+    /// The shape to play when the guide says `encoding` against
+    /// `opponent_shape`.
+    fn decode(&self, opponent_shape: HandShape, encoding: SecondEncoding) -> HandShape;
+}
+
+/// Reads each letter as the shape to play.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Mapping {
+    pub x: HandShape,
+    pub y: HandShape,
+    pub z: HandShape,
+}
+
+impl Mapping {
+    /// The reading assumed by the first part of the puzzle.
+    pub const SHAPES: Self = Self {
+        x: HandShape::Rock,
+        y: HandShape::Paper,
+        z: HandShape::Scissors,
+    };
+
+    /// All 27 mappings, including those reading several letters as the
+    /// same shape.
+    pub fn all() -> impl Iterator<Item = Self> {
+        HandShape::ALL.into_iter().flat_map(|x| {
+            HandShape::ALL
+                .into_iter()
+                .flat_map(move |y| HandShape::ALL.into_iter().map(move |z| Self { x, y, z }))
+        })
+    }
+
+    /// The 3! mappings reading each letter as a different shape.
+    pub fn permutations() -> impl Iterator<Item = Self> {
+        Self::all().filter(|Self { x, y, z }| x != y && y != z && x != z)
+    }
+}
+
+impl Decoder for Mapping {
+    fn decode(&self, _opponent_shape: HandShape, encoding: SecondEncoding) -> HandShape {
+        match encoding {
+            SecondEncoding::X => self.x,
+            SecondEncoding::Y => self.y,
+            SecondEncoding::Z => self.z,
+        }
+    }
+}
+
+impl fmt::Display for Mapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "X={:?} Y={:?} Z={:?}", self.x, self.y, self.z)
+    }
+}
+
+/// Reads each letter as how the round should end.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OutcomeMapping {
+    pub x: Outcome,
+    pub y: Outcome,
+    pub z: Outcome,
+}
+
+impl OutcomeMapping {
+    /// The reading revealed in the second part of the puzzle.
+    pub const OUTCOMES: Self = Self {
+        x: Outcome::Lose,
+        y: Outcome::Draw,
+        z: Outcome::Win,
+    };
+
+    /// The 3! mappings reading each letter as a different outcome.
+    pub fn permutations() -> impl Iterator<Item = Self> {
+        Mapping::permutations().map(|Mapping { x, y, z }| {
+            let outcome = |shape| match shape {
+                HandShape::Rock => Outcome::Lose,
+                HandShape::Paper => Outcome::Draw,
+                HandShape::Scissors => Outcome::Win,
+            };
+
+            Self {
+                x: outcome(x),
+                y: outcome(y),
+                z: outcome(z),
+            }
+        })
+    }
+}
+
+impl Decoder for OutcomeMapping {
+    fn decode(&self, opponent_shape: HandShape, encoding: SecondEncoding) -> HandShape {
+        let outcome = match encoding {
+            SecondEncoding::X => self.x,
+            SecondEncoding::Y => self.y,
+            SecondEncoding::Z => self.z,
+        };
+
+        match outcome {
+            Outcome::Lose => opponent_shape.prev(),
+            Outcome::Draw => opponent_shape,
+            Outcome::Win => opponent_shape.next(),
+        }
+    }
+}
+
+impl fmt::Display for OutcomeMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "X={:?} Y={:?} Z={:?}", self.x, self.y, self.z)
+    }
+}
+
+/// Either reading of the second column.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Interpretation {
+    Shapes(Mapping),
+    Outcomes(OutcomeMapping),
+}
+
+impl Interpretation {
+    /// Every one-to-one reading of the letters, as shapes and as outcomes.
+    pub fn all() -> impl Iterator<Item = Self> {
+        Mapping::permutations()
+            .map(Self::Shapes)
+            .chain(OutcomeMapping::permutations().map(Self::Outcomes))
+    }
+}
+
+impl Decoder for Interpretation {
+    fn decode(&self, opponent_shape: HandShape, encoding: SecondEncoding) -> HandShape {
+        match self {
+            Self::Shapes(mapping) => mapping.decode(opponent_shape, encoding),
+            Self::Outcomes(mapping) => mapping.decode(opponent_shape, encoding),
+        }
+    }
+}
+
+impl fmt::Display for Interpretation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Shapes(mapping) => write!(f, "shapes {mapping}"),
+            Self::Outcomes(mapping) => write!(f, "outcomes {mapping}"),
+        }
+    }
+}
+
+impl Input {
+    /// The total score of following the guide as read by `decoder`.
+    pub fn score(&self, decoder: &impl Decoder) -> u32 {
+        self.0
+            .iter()
+            .map(|Round(fst, snd)| {
+                let opponent_shape = HandShape::from(*fst);
+
+                decoder
+                    .decode(opponent_shape, *snd)
+                    .round_score(&opponent_shape)
+            })
+            .sum()
+    }
+
+    /// The score of every interpretation in [`Interpretation::all`].
+    pub fn evaluate(&self) -> Vec<(Interpretation, u32)> {
+        Interpretation::all()
+            .map(|interpretation| (interpretation, self.score(&interpretation)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::INPUT;
+
+    #[test]
+    fn test_permutations() {
+        assert_eq!(Mapping::all().count(), 27);
+        assert_eq!(Mapping::permutations().count(), 6);
+        assert_eq!(Mapping::permutations().next(), Some(Mapping::SHAPES));
+        assert_eq!(
+            OutcomeMapping::permutations().next(),
+            Some(OutcomeMapping::OUTCOMES)
+        );
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let input: Input = INPUT.parse().unwrap();
+        let scores: Vec<_> = input
+            .evaluate()
+            .into_iter()
+            .map(|(interpretation, score)| format!("{interpretation}: {score}"))
+            .collect();
+
+        assert_eq!(
+            scores,
+            [
+                "shapes X=Rock Y=Paper Z=Scissors: 15",
+                "shapes X=Rock Y=Scissors Z=Paper: 6",
+                "shapes X=Paper Y=Rock Z=Scissors: 15",
+                "shapes X=Paper Y=Scissors Z=Rock: 15",
+                "shapes X=Scissors Y=Rock Z=Paper: 15",
+                "shapes X=Scissors Y=Paper Z=Rock: 24",
+                "outcomes X=Lose Y=Draw Z=Win: 12",
+                "outcomes X=Lose Y=Win Z=Draw: 15",
+                "outcomes X=Draw Y=Lose Z=Win: 15",
+                "outcomes X=Draw Y=Win Z=Lose: 15",
+                "outcomes X=Win Y=Lose Z=Draw: 18",
+                "outcomes X=Win Y=Draw Z=Lose: 15",
+            ]
+        );
+    }
+}
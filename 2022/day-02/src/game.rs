@@ -0,0 +1,226 @@
+use thiserror::Error;
+
+/// A shape of a [`Game`], by its position around the game's circle.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Shape(pub usize);
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OutcomeScores {
+    pub lose: u32,
+    pub draw: u32,
+    pub win: u32,
+}
+
+impl Default for OutcomeScores {
+    fn default() -> Self {
+        Self {
+            lose: 0,
+            draw: 3,
+            win: 6,
+        }
+    }
+}
+
+impl OutcomeScores {
+    pub fn get(&self, outcome: Outcome) -> u32 {
+        match outcome {
+            Outcome::Lose => self.lose,
+            Outcome::Draw => self.draw,
+            Outcome::Win => self.win,
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum GameError {
+    #[error("a cyclic game needs an odd number of at least three shapes, found {0}")]
+    InvalidShapeCount(usize),
+    #[error("expected a score for each of the {shapes} shapes, found {scores}")]
+    ScoreCountMismatch { shapes: usize, scores: usize },
+}
+
+/// A generalised rock-paper-scissors. The shapes sit on a circle and each
+/// one beats the half of the others that come just before it, so every
+/// shape wins against as many shapes as it loses to.
+#[derive(Clone, Debug)]
+pub struct Game {
+    names: Vec<String>,
+    shape_scores: Vec<u32>,
+    outcome_scores: OutcomeScores,
+}
+
+impl Game {
+    pub fn new(
+        names: Vec<String>,
+        shape_scores: Vec<u32>,
+        outcome_scores: OutcomeScores,
+    ) -> Result<Self, GameError> {
+        if names.len() < 3 || names.len().is_multiple_of(2) {
+            return Err(GameError::InvalidShapeCount(names.len()));
+        }
+
+        if shape_scores.len() != names.len() {
+            return Err(GameError::ScoreCountMismatch {
+                shapes: names.len(),
+                scores: shape_scores.len(),
+            });
+        }
+
+        Ok(Self {
+            names,
+            shape_scores,
+            outcome_scores,
+        })
+    }
+
+    /// A game over `names` scored like the puzzle: each shape is worth one
+    /// point more than the one before it.
+    pub fn cyclic(names: &[&str]) -> Result<Self, GameError> {
+        Self::new(
+            names.iter().map(|&name| name.to_owned()).collect(),
+            (1..=names.len() as u32).collect(),
+            OutcomeScores::default(),
+        )
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        Self::cyclic(&["Rock", "Paper", "Scissors"]).expect("three shapes")
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Self::cyclic(&["Rock", "Spock", "Paper", "Lizard", "Scissors"]).expect("five shapes")
+    }
+
+    pub fn shape_count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn shapes(&self) -> impl Iterator<Item = Shape> {
+        (0..self.shape_count()).map(Shape)
+    }
+
+    pub fn name(&self, shape: Shape) -> &str {
+        &self.names[shape.0]
+    }
+
+    pub fn shape(&self, name: &str) -> Option<Shape> {
+        self.names.iter().position(|n| n == name).map(Shape)
+    }
+
+    pub fn outcome(&self, mine: Shape, theirs: Shape) -> Outcome {
+        let n = self.shape_count();
+
+        match (mine.0 + n - theirs.0) % n {
+            0 => Outcome::Draw,
+            distance if distance <= n / 2 => Outcome::Win,
+            _ => Outcome::Lose,
+        }
+    }
+
+    /// Points for playing `mine` against `theirs`.
+    pub fn score(&self, mine: Shape, theirs: Shape) -> u32 {
+        self.shape_scores[mine.0] + self.outcome_scores.get(self.outcome(mine, theirs))
+    }
+
+    /// The shapes that win against `shape`.
+    pub fn beating(&self, shape: Shape) -> impl Iterator<Item = Shape> {
+        let n = self.shape_count();
+
+        (1..=n / 2).map(move |distance| Shape((shape.0 + distance) % n))
+    }
+
+    /// The shapes that lose against `shape`.
+    pub fn beaten_by(&self, shape: Shape) -> impl Iterator<Item = Shape> {
+        let n = self.shape_count();
+
+        (1..=n / 2).map(move |distance| Shape((shape.0 + n - distance) % n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let names = |n: usize| (0..n).map(|i| i.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            Game::new(names(4), vec![1; 4], OutcomeScores::default()).err(),
+            Some(GameError::InvalidShapeCount(4))
+        );
+        assert_eq!(
+            Game::new(names(0), vec![], OutcomeScores::default()).err(),
+            Some(GameError::InvalidShapeCount(0))
+        );
+        // A single shape beats nothing and is beaten by nothing.
+        assert_eq!(
+            Game::cyclic(&["Only"]).err(),
+            Some(GameError::InvalidShapeCount(1))
+        );
+        assert_eq!(
+            Game::new(names(3), vec![1; 2], OutcomeScores::default()).err(),
+            Some(GameError::ScoreCountMismatch {
+                shapes: 3,
+                scores: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_rock_paper_scissors_lizard_spock() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let shape = |name| game.shape(name).unwrap();
+
+        for (winner, loser) in [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ] {
+            assert_eq!(game.outcome(shape(winner), shape(loser)), Outcome::Win);
+            assert_eq!(game.outcome(shape(loser), shape(winner)), Outcome::Lose);
+        }
+
+        for shape in game.shapes() {
+            assert_eq!(game.outcome(shape, shape), Outcome::Draw);
+            assert!(game
+                .beating(shape)
+                .all(|other| game.outcome(other, shape) == Outcome::Win));
+            assert!(game
+                .beaten_by(shape)
+                .all(|other| game.outcome(other, shape) == Outcome::Lose));
+        }
+    }
+
+    #[test]
+    fn test_score() {
+        let game = Game::new(
+            vec!["A".into(), "B".into(), "C".into()],
+            vec![10, 20, 30],
+            OutcomeScores {
+                lose: 1,
+                draw: 2,
+                win: 3,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(game.score(Shape(0), Shape(1)), 11);
+        assert_eq!(game.score(Shape(1), Shape(1)), 22);
+        assert_eq!(game.score(Shape(2), Shape(1)), 33);
+    }
+}
@@ -1,44 +1,23 @@
-use std::{char::ParseCharError, cmp::Ordering, str::FromStr};
+mod decoder;
+mod game;
+mod optimiser;
+mod tournament;
+
+pub use decoder::{Decoder, Interpretation, Mapping, OutcomeMapping};
+pub use game::{Game, GameError, Outcome, OutcomeScores, Shape};
+use std::{char::ParseCharError, str::FromStr};
 use thiserror::Error;
+pub use tournament::{
+    BeatLast, Constant, Cycle, Entrant, Player, Random, Standing, Standings, Tally, Throw,
+    Tournament,
+};
 
-impl From<Strategy> for HandShape {
-    fn from(value: Strategy) -> Self {
-        match value {
-            Strategy::Win => HandShape::Rock,
-            Strategy::Draw => HandShape::Paper,
-            Strategy::Lose => HandShape::Scissors,
-        }
-    }
+pub fn part_1(input: Input) -> u32 {
+    input.score(&Mapping::SHAPES)
 }
 
-pub fn part_1(Input(rounds): Input) -> u32 {
-    rounds
-        .into_iter()
-        .map(|Round(fst, snd)| {
-            let opponent_shape = HandShape::from(fst);
-            let my_shape = HandShape::from(snd);
-            (opponent_shape, my_shape)
-        })
-        .map(|(opponent_shape, my_shape)| my_shape.outcome(&opponent_shape) + my_shape.score())
-        .sum()
-}
-
-pub fn part_2(Input(rounds): Input) -> u32 {
-    rounds
-        .into_iter()
-        .map(|Round(fst, snd)| {
-            let opponent_shape = HandShape::from(fst);
-            let strategy = Strategy::from(snd);
-            let my_shape = match strategy {
-                Strategy::Win => opponent_shape.next(),
-                Strategy::Draw => opponent_shape,
-                Strategy::Lose => opponent_shape.prev(),
-            };
-
-            (opponent_shape, my_shape)
-        })
-        .map(|(opponent_shape, my_shape)| my_shape.outcome(&opponent_shape) + my_shape.score())
-        .sum()
+pub fn part_2(input: Input) -> u32 {
+    input.score(&OutcomeMapping::OUTCOMES)
 }
 
 pub struct Round(FirstEncoding, SecondEncoding);
@@ -62,32 +41,22 @@ impl FromStr for Round {
     }
 }
 
-enum Strategy {
-    Lose,
-    Draw,
-    Win,
-}
-
-#[derive(Clone, Copy, Eq, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum HandShape {
     Rock,
     Paper,
     Scissors,
 }
 
-impl Ord for HandShape {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if self == other {
-            Ordering::Equal
-        } else if self.next() == *other {
-            Ordering::Less
-        } else {
-            Ordering::Greater
-        }
+impl From<HandShape> for Shape {
+    fn from(value: HandShape) -> Self {
+        Shape(value as usize)
     }
 }
 
 impl HandShape {
+    pub const ALL: [Self; 3] = [Self::Rock, Self::Paper, Self::Scissors];
+
     fn score(&self) -> u32 {
         match self {
             Self::Rock => 1,
@@ -97,10 +66,12 @@ impl HandShape {
     }
 
     fn outcome(&self, other: &Self) -> u32 {
-        match self.cmp(other) {
-            Ordering::Less => 0,
-            Ordering::Equal => 3,
-            Ordering::Greater => 6,
+        if self == other {
+            3
+        } else if self.next() == *other {
+            0
+        } else {
+            6
         }
     }
 
@@ -115,8 +86,14 @@ impl HandShape {
     fn prev(&self) -> Self {
         self.next().next()
     }
+
+    /// Points for playing `self` against `opponent`.
+    fn round_score(&self, opponent: &Self) -> u32 {
+        self.outcome(opponent) + self.score()
+    }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum FirstEncoding {
     A,
     B,
@@ -154,32 +131,13 @@ impl FromStr for FirstEncoding {
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum SecondEncoding {
     X,
     Y,
     Z,
 }
 
-impl From<SecondEncoding> for Strategy {
-    fn from(value: SecondEncoding) -> Self {
-        match value {
-            SecondEncoding::X => Self::Lose,
-            SecondEncoding::Y => Self::Draw,
-            SecondEncoding::Z => Self::Win,
-        }
-    }
-}
-
-impl From<SecondEncoding> for HandShape {
-    fn from(value: SecondEncoding) -> Self {
-        match value {
-            SecondEncoding::X => Self::Rock,
-            SecondEncoding::Y => Self::Paper,
-            SecondEncoding::Z => Self::Scissors,
-        }
-    }
-}
-
 #[derive(Debug, Error)]
 pub enum SecondEncodingError {
     #[error(transparent)]
@@ -228,7 +186,7 @@ impl FromStr for Input {
 mod tests {
     use super::*;
 
-    const INPUT: &str = "\
+    pub(crate) const INPUT: &str = "\
 A Y
 B X
 C Z
@@ -247,4 +205,17 @@ C Z
 
         assert_eq!(answer, 12);
     }
+
+    #[test]
+    fn test_rock_paper_scissors() {
+        let game = Game::rock_paper_scissors();
+        for mine in HandShape::ALL {
+            for theirs in HandShape::ALL {
+                assert_eq!(
+                    game.score(mine.into(), theirs.into()),
+                    mine.outcome(&theirs) + mine.score()
+                );
+            }
+        }
+    }
 }
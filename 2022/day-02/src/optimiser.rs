@@ -0,0 +1,94 @@
+use crate::{Decoder, FirstEncoding, HandShape, Input, Mapping, Round, SecondEncoding};
+
+const FIRST: [FirstEncoding; 3] = [FirstEncoding::A, FirstEncoding::B, FirstEncoding::C];
+const SECOND: [SecondEncoding; 3] = [SecondEncoding::X, SecondEncoding::Y, SecondEncoding::Z];
+
+impl Input {
+    /// The highest scoring shape to play in each round.
+    pub fn best_responses(&self) -> Vec<HandShape> {
+        self.0
+            .iter()
+            .map(|Round(fst, _)| {
+                let opponent_shape = HandShape::from(*fst);
+
+                HandShape::ALL
+                    .into_iter()
+                    .max_by_key(|shape| shape.round_score(&opponent_shape))
+                    .expect("three shapes")
+            })
+            .collect()
+    }
+
+    /// The score of playing [`Input::best_responses`].
+    pub fn max_score(&self) -> u32 {
+        self.0
+            .iter()
+            .zip(self.best_responses())
+            .map(|(Round(fst, _), my_shape)| my_shape.round_score(&HandShape::from(*fst)))
+            .sum()
+    }
+
+    /// A mapping of the second column under which the guide scores exactly
+    /// `target`, if there is one.
+    pub fn mapping_for(&self, target: u32) -> Option<Mapping> {
+        // Only how often each pair of letters comes up matters.
+        let mut counts = [[0; 3]; 3];
+
+        for Round(fst, snd) in &self.0 {
+            counts[*fst as usize][*snd as usize] += 1;
+        }
+
+        Mapping::all().find(|mapping| {
+            let score: u32 = FIRST
+                .iter()
+                .flat_map(|&fst| SECOND.iter().map(move |&snd| (fst, snd)))
+                .map(|(fst, snd)| {
+                    let opponent_shape = HandShape::from(fst);
+                    let my_shape = mapping.decode(opponent_shape, snd);
+
+                    counts[fst as usize][snd as usize] * my_shape.round_score(&opponent_shape)
+                })
+                .sum();
+
+            score == target
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::INPUT;
+
+    #[test]
+    fn test_best_responses() {
+        let input: Input = INPUT.parse().unwrap();
+
+        assert_eq!(
+            input.best_responses(),
+            [HandShape::Paper, HandShape::Scissors, HandShape::Rock]
+        );
+        assert_eq!(input.max_score(), 8 + 9 + 7);
+    }
+
+    #[test]
+    fn test_mapping_for() {
+        let input: Input = INPUT.parse().unwrap();
+
+        for target in 0..=30 {
+            match input.mapping_for(target) {
+                Some(mapping) => assert_eq!(input.score(&mapping), target),
+                None => assert!(Mapping::all().all(|m| input.score(&m) != target)),
+            }
+        }
+
+        assert_eq!(
+            input.mapping_for(input.max_score()),
+            Some(Mapping {
+                x: HandShape::Scissors,
+                y: HandShape::Paper,
+                z: HandShape::Rock
+            })
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use crate::game::{Game, Outcome, Shape};
+use std::{
+    cmp::{Ordering, Reverse},
+    fmt,
+};
+
+/// One round of a match, seen from one of the two players.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Throw {
+    pub mine: Shape,
+    pub theirs: Shape,
+}
+
+/// Picks a shape for the next round of a match, knowing the rounds so far.
+pub trait Player {
+    fn choose(&mut self, game: &Game, history: &[Throw]) -> Shape;
+}
+
+/// Always plays the same shape.
+pub struct Constant(pub Shape);
+
+impl Player for Constant {
+    fn choose(&mut self, _game: &Game, _history: &[Throw]) -> Shape {
+        self.0
+    }
+}
+
+/// Plays every shape in turn.
+pub struct Cycle;
+
+impl Player for Cycle {
+    fn choose(&mut self, game: &Game, history: &[Throw]) -> Shape {
+        Shape(history.len() % game.shape_count())
+    }
+}
+
+/// Plays a shape that beats the opponent's previous one.
+pub struct BeatLast;
+
+impl Player for BeatLast {
+    fn choose(&mut self, game: &Game, history: &[Throw]) -> Shape {
+        history.last().map_or(Shape(0), |throw| {
+            game.beating(throw.theirs)
+                .next()
+                .expect("at least one shape")
+        })
+    }
+}
+
+/// Plays uniformly random shapes from a seeded SplitMix64 generator.
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl Player for Random {
+    fn choose(&mut self, game: &Game, _history: &[Throw]) -> Shape {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        Shape((z % game.shape_count() as u64) as usize)
+    }
+}
+
+pub struct Entrant {
+    pub name: String,
+    pub player: Box<dyn Player>,
+}
+
+impl Entrant {
+    pub fn new(name: impl Into<String>, player: impl Player + 'static) -> Self {
+        Self {
+            name: name.into(),
+            player: Box::new(player),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Tally {
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Tally {
+    pub fn played(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+    }
+
+    fn record(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Lose => self.losses += 1,
+            Outcome::Draw => self.draws += 1,
+            Outcome::Win => self.wins += 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    /// Matches are won by winning more of their rounds.
+    pub matches: Tally,
+    pub rounds: Tally,
+    pub score: u64,
+}
+
+impl Standing {
+    pub fn mean_score(&self) -> f64 {
+        match self.rounds.played() {
+            0 => 0.0,
+            rounds => self.score as f64 / rounds as f64,
+        }
+    }
+}
+
+/// Standings of a tournament, highest score first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standings(pub Vec<Standing>);
+
+impl fmt::Display for Standings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.0.iter().map(|s| s.name.len()).max().unwrap_or(0);
+
+        writeln!(
+            f,
+            "{:width$}  {:>9}  {:>20}  {:>10}  {:>6}",
+            "player", "matches", "rounds", "score", "mean"
+        )?;
+
+        for standing in &self.0 {
+            let Standing {
+                name,
+                matches: m,
+                rounds: r,
+                score,
+            } = standing;
+
+            writeln!(
+                f,
+                "{name:width$}  {:>9}  {:>20}  {score:>10}  {:>6.3}",
+                format!("{}-{}-{}", m.wins, m.draws, m.losses),
+                format!("{}-{}-{}", r.wins, r.draws, r.losses),
+                standing.mean_score(),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A round robin where every entrant plays every other one over a fixed
+/// number of rounds.
+pub struct Tournament<'a> {
+    pub game: &'a Game,
+    pub rounds: usize,
+}
+
+impl Tournament<'_> {
+    pub fn run(&self, entrants: &mut [Entrant]) -> Standings {
+        let mut standings: Vec<_> = entrants
+            .iter()
+            .map(|entrant| Standing {
+                name: entrant.name.clone(),
+                matches: Tally::default(),
+                rounds: Tally::default(),
+                score: 0,
+            })
+            .collect();
+
+        for a in 0..entrants.len() {
+            for b in a + 1..entrants.len() {
+                let (left, right) = entrants.split_at_mut(b);
+                let (rounds_a, rounds_b) = self.play(&mut left[a], &mut right[0]);
+
+                for (idx, rounds) in [(a, rounds_a), (b, rounds_b)] {
+                    let standing = &mut standings[idx];
+                    let (tally, score) = rounds;
+
+                    standing.rounds.merge(tally);
+                    standing.score += score;
+                    standing
+                        .matches
+                        .record(match tally.wins.cmp(&tally.losses) {
+                            Ordering::Less => Outcome::Lose,
+                            Ordering::Equal => Outcome::Draw,
+                            Ordering::Greater => Outcome::Win,
+                        });
+                }
+            }
+        }
+
+        standings.sort_by_key(|standing| Reverse(standing.score));
+
+        Standings(standings)
+    }
+
+    /// Plays one match, returning each side's rounds and score.
+    fn play(&self, a: &mut Entrant, b: &mut Entrant) -> ((Tally, u64), (Tally, u64)) {
+        let mut history_a = Vec::with_capacity(self.rounds);
+        let mut history_b = Vec::with_capacity(self.rounds);
+        let mut results = ((Tally::default(), 0), (Tally::default(), 0));
+
+        for _ in 0..self.rounds {
+            let shape_a = a.player.choose(self.game, &history_a);
+            let shape_b = b.player.choose(self.game, &history_b);
+
+            for ((tally, score), mine, theirs) in [
+                (&mut results.0, shape_a, shape_b),
+                (&mut results.1, shape_b, shape_a),
+            ] {
+                tally.record(self.game.outcome(mine, theirs));
+                *score += u64::from(self.game.score(mine, theirs));
+            }
+
+            history_a.push(Throw {
+                mine: shape_a,
+                theirs: shape_b,
+            });
+            history_b.push(Throw {
+                mine: shape_b,
+                theirs: shape_a,
+            });
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_players() {
+        let game = Game::rock_paper_scissors();
+        let history = [Throw {
+            mine: Shape(0),
+            theirs: Shape(2),
+        }];
+
+        assert_eq!(Constant(Shape(1)).choose(&game, &history), Shape(1));
+        assert_eq!(Cycle.choose(&game, &history), Shape(1));
+        assert_eq!(BeatLast.choose(&game, &[]), Shape(0));
+        assert_eq!(BeatLast.choose(&game, &history), Shape(0));
+
+        let mut random = Random::new(7);
+        let shapes: Vec<_> = (0..300).map(|_| random.choose(&game, &[]).0).collect();
+
+        assert!((0..3).all(|shape| shapes.iter().filter(|&&s| s == shape).count() > 70));
+    }
+
+    #[test]
+    fn test_tournament() {
+        let game = Game::rock_paper_scissors();
+        let mut entrants = [
+            Entrant::new("rock", Constant(Shape(0))),
+            Entrant::new("paper", Constant(Shape(1))),
+            Entrant::new("beat last", BeatLast),
+        ];
+
+        let Standings(standings) = Tournament {
+            game: &game,
+            rounds: 10,
+        }
+        .run(&mut entrants);
+
+        let names: Vec<_> = standings.iter().map(|s| s.name.as_str()).collect();
+
+        // After its opening round, "beat last" answers a constant player
+        // with the shape that beats it every time.
+        assert_eq!(names, ["beat last", "paper", "rock"]);
+        assert_eq!(
+            standings[0].rounds,
+            Tally {
+                wins: 18,
+                draws: 1,
+                losses: 1
+            }
+        );
+        assert_eq!(
+            standings[0].matches,
+            Tally {
+                wins: 2,
+                draws: 0,
+                losses: 0
+            }
+        );
+        // Rock loses every round to paper, then draws once and loses nine
+        // times to "beat last".
+        assert_eq!(standings[2].score, 10 + 4 + 9);
+        assert!(standings.iter().all(|s| s.rounds.played() == 20));
+    }
+}
@@ -0,0 +1,158 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day_03::{part_1, part_2, ItemSet, ItemType};
+use std::collections::HashSet;
+
+const IDS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Generates groups that keep the puzzle's rules: one item type shared by
+/// the compartments of each rucksack and one badge shared by each group.
+fn synthetic_input(groups: usize) -> String {
+    let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+    let mut next = move |bound: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % bound as u64) as usize
+    };
+
+    let mut input = String::new();
+
+    for _ in 0..groups {
+        let mut ids = IDS.to_vec();
+        let badge = ids.swap_remove(next(ids.len()));
+
+        // Each rucksack draws from its own 17 ids so only the badge is common,
+        // and each compartment from its own part of those but for `shared`.
+        for pool in ids.chunks(17) {
+            let (shared, pool) = pool.split_first().unwrap();
+            let (left, right) = pool.split_at(8);
+            let size = 12 + next(12);
+
+            let first: Vec<u8> = [badge, *shared]
+                .into_iter()
+                .chain((2..size).map(|_| left[next(left.len())]))
+                .collect();
+            let second: Vec<u8> = [*shared]
+                .into_iter()
+                .chain((1..size).map(|_| right[next(right.len())]))
+                .collect();
+
+            input.extend(first.iter().chain(&second).map(|&id| id as char));
+            input.push('\n');
+        }
+    }
+
+    input
+}
+
+fn hash_set_priorities(input: &str) -> u32 {
+    let priority = |id: char| ItemType::new(id).unwrap().priority() as u32;
+    let lines: Vec<_> = input.lines().collect();
+
+    let shared: u32 = lines
+        .iter()
+        .map(|line| {
+            let (first, second) = line.split_at(line.len() / 2);
+            let first: HashSet<char> = first.chars().collect();
+            let second: HashSet<char> = second.chars().collect();
+
+            first
+                .intersection(&second)
+                .map(|&id| priority(id))
+                .sum::<u32>()
+        })
+        .sum();
+
+    let badges: u32 = lines
+        .chunks(3)
+        .map(|group| {
+            group
+                .iter()
+                .map(|line| line.chars().collect::<HashSet<_>>())
+                .reduce(|acc, set| acc.intersection(&set).cloned().collect())
+                .unwrap()
+                .into_iter()
+                .map(priority)
+                .sum::<u32>()
+        })
+        .sum();
+
+    shared + badges
+}
+
+fn item_set_priorities(input: &str) -> u32 {
+    let set = |s: &str| {
+        s.chars()
+            .map(|id| ItemType::new(id).unwrap())
+            .collect::<ItemSet>()
+    };
+    let lines: Vec<_> = input.lines().collect();
+
+    let shared: u32 = lines
+        .iter()
+        .map(|line| {
+            let (first, second) = line.split_at(line.len() / 2);
+
+            (set(first) & set(second))
+                .iter()
+                .map(|item_type| item_type.priority() as u32)
+                .sum::<u32>()
+        })
+        .sum();
+
+    let badges: u32 = lines
+        .chunks(3)
+        .map(|group| {
+            group
+                .iter()
+                .map(|line| set(line))
+                .fold(ItemSet::full(), |acc, set| acc & set)
+                .iter()
+                .map(|item_type| item_type.priority() as u32)
+                .sum::<u32>()
+        })
+        .sum();
+
+    shared + badges
+}
+
+fn bench_sets(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sets");
+
+    for groups in [1_000, 10_000, 100_000] {
+        let input = synthetic_input(groups);
+
+        assert_eq!(hash_set_priorities(&input), item_set_priorities(&input));
+
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::new("hash_set", groups), &input, |b, input| {
+            b.iter(|| hash_set_priorities(input))
+        });
+        group.bench_with_input(BenchmarkId::new("item_set", groups), &input, |b, input| {
+            b.iter(|| item_set_priorities(input))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_parts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parts");
+
+    for groups in [1_000, 10_000, 100_000] {
+        let input = synthetic_input(groups);
+
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::new("part_1", groups), &input, |b, input| {
+            b.iter(|| part_1(input.parse().unwrap()))
+        });
+        group.bench_with_input(BenchmarkId::new("part_2", groups), &input, |b, input| {
+            b.iter(|| part_2(input.parse().unwrap()))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_sets, bench_parts);
+criterion_main!(benches);
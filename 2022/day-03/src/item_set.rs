@@ -0,0 +1,138 @@
+use crate::ItemType;
+use std::ops::{BitAnd, BitOr};
+
+/// A set of item types, one bit per priority.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every item type there is.
+    pub fn full() -> Self {
+        Self((1 << 52) - 1)
+    }
+
+    fn bit(item_type: ItemType) -> u64 {
+        1 << (item_type.priority() - 1)
+    }
+
+    pub fn insert(&mut self, item_type: ItemType) {
+        self.0 |= Self::bit(item_type);
+    }
+
+    pub fn contains(&self, item_type: ItemType) -> bool {
+        self.0 & Self::bit(item_type) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// The item types in the set by increasing priority.
+    pub fn iter(self) -> Iter {
+        Iter(self.0)
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl FromIterator<ItemType> for ItemSet {
+    fn from_iter<T: IntoIterator<Item = ItemType>>(iter: T) -> Self {
+        let mut set = Self::new();
+
+        for item_type in iter {
+            set.insert(item_type);
+        }
+
+        set
+    }
+}
+
+impl IntoIterator for ItemSet {
+    type Item = ItemType;
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter(u64);
+
+impl Iterator for Iter {
+    type Item = ItemType;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let priority = self.0.trailing_zeros() as u8 + 1;
+        self.0 &= self.0 - 1;
+
+        ItemType::from_priority(priority)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ids: &str) -> ItemSet {
+        ids.chars().map(|c| ItemType::new(c).unwrap()).collect()
+    }
+
+    fn ids(set: ItemSet) -> String {
+        set.iter().map(|item_type| item_type.id).collect()
+    }
+
+    #[test]
+    fn test_item_set() {
+        let a = set("vJrwpWtwJgWr");
+        let b = set("hcsFMMfFFhFp");
+
+        assert_eq!(ids(a), "gprtvwJW");
+        assert_eq!(a.len(), 8);
+        assert!(a.contains(ItemType::new('J').unwrap()));
+        assert!(!a.contains(ItemType::new('j').unwrap()));
+        assert_eq!(ids(a & b), "p");
+        assert_eq!(ids(a | b), "cfghprstvwFJMW");
+        assert!(ItemSet::new().is_empty());
+        assert_eq!(ItemSet::full().len(), 52);
+        assert_eq!(ids(ItemSet::full()).len(), 52);
+    }
+}
@@ -1,4 +1,7 @@
-use std::{collections::HashSet, str::FromStr};
+mod item_set;
+
+pub use item_set::ItemSet;
+use std::str::FromStr;
 use thiserror::Error;
 
 pub fn part_1(input: Input) -> u32 {
@@ -6,16 +9,20 @@ pub fn part_1(input: Input) -> u32 {
         .groups
         .iter()
         .flat_map(|g| &g.rucksacks)
-        .map(|rucksack| rucksack.shared_item_type().priority() as u32)
+        .map(|rucksack| priorities(rucksack.shared_item_types()))
         .sum()
 }
 
 pub fn part_2(input: Input) -> u32 {
-    input.groups.iter().map(|g| g.badge.priority() as u32).sum()
+    input.groups.iter().map(|g| priorities(g.badges)).sum()
+}
+
+fn priorities(item_types: ItemSet) -> u32 {
+    item_types.iter().map(|i| i.priority() as u32).sum()
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct ItemType {
+pub struct ItemType {
     id: char,
 }
 
@@ -26,107 +33,108 @@ pub enum ItemTypeError {
 }
 
 impl ItemType {
-    fn new(id: char) -> Result<Self, ItemTypeError> {
+    pub fn new(id: char) -> Result<Self, ItemTypeError> {
         match id {
             'a'..='z' | 'A'..='Z' => Ok(Self { id }),
             _ => Err(ItemTypeError::InvalidId(id)),
         }
     }
 
-    fn priority(&self) -> u8 {
+    pub fn id(&self) -> char {
+        self.id
+    }
+
+    pub fn priority(&self) -> u8 {
         match self.id {
             'a'..='z' => (self.id as u8) - 96,
             'A'..='Z' => (self.id as u8) - 38,
             _ => unreachable!(),
         }
     }
+
+    fn from_priority(priority: u8) -> Option<Self> {
+        match priority {
+            1..=26 => Some(Self {
+                id: (priority + 96) as char,
+            }),
+            27..=52 => Some(Self {
+                id: (priority + 38) as char,
+            }),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
 struct Compartment {
-    pub item_types: HashSet<ItemType>,
-}
-
-impl FromStr for Compartment {
-    type Err = CompartmentError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        s.chars()
-            .enumerate()
-            .map(|(idx, c)| {
-                ItemType::new(c).map_err(|err| CompartmentError::InvalidItemType { idx, err })
-            })
-            .collect::<Result<HashSet<_>, _>>()
-            .map(|item_types| Compartment { item_types })
-    }
+    pub item_types: ItemSet,
 }
 
 #[derive(Debug)]
-struct Rucksack {
+pub struct Rucksack {
     compartments: [Compartment; 2],
 }
 
 impl Rucksack {
-    pub fn shared_item_type(&self) -> &ItemType {
-        self.compartments[0]
-            .item_types
-            .intersection(&self.compartments[1].item_types)
-            .next()
-            .expect("one shared item type")
+    /// Item types found in both compartments.
+    pub fn shared_item_types(&self) -> ItemSet {
+        self.compartments[0].item_types & self.compartments[1].item_types
     }
-}
 
-impl FromStr for Rucksack {
-    type Err = RucksackError;
+    pub fn item_types(&self) -> ItemSet {
+        self.compartments[0].item_types | self.compartments[1].item_types
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let len = s.len();
+    fn parse(s: &str, format: &InputFormat) -> Result<Self, RucksackError> {
+        let item_types = s
+            .chars()
+            .enumerate()
+            .map(|(idx, c)| {
+                ItemType::new(c).map_err(|err| RucksackError::InvalidItemType {
+                    column: idx + 1,
+                    err,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let len = item_types.len();
 
         if len & 1 == 1 {
-            return Err(Self::Err::UnequalCompartmentSize);
+            return Err(RucksackError::UnequalCompartmentSize { len });
         }
 
-        let (s_1, s_2) = s.split_at(len / 2);
-
-        let compartment_1 = s_1
-            .parse::<Compartment>()
-            .map_err(Self::Err::Compartment1Error)?;
+        let (items_1, items_2) = item_types.split_at(len / 2);
 
-        let compartment_2 = s_2
-            .parse::<Compartment>()
-            .map_err(Self::Err::Compartment2Error)?;
+        let rucksack = Self {
+            compartments: [items_1, items_2].map(|items| Compartment {
+                item_types: items.iter().copied().collect(),
+            }),
+        };
 
-        if compartment_1
-            .item_types
-            .intersection(&compartment_2.item_types)
-            .count()
-            != 1
-        {
-            return Err(Self::Err::MultipleSharedItemTypes);
+        if !format.lenient && rucksack.shared_item_types().len() != 1 {
+            return Err(RucksackError::MultipleSharedItemTypes);
         };
 
-        Ok(Self {
-            compartments: [compartment_1, compartment_2],
-        })
+        Ok(rucksack)
+    }
+}
+
+impl FromStr for Rucksack {
+    type Err = RucksackError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, &InputFormat::default())
     }
 }
 
 #[derive(Debug, Error)]
 pub enum RucksackError {
-    #[error("the size of the compartments is not equal")]
-    UnequalCompartmentSize,
+    #[error("the size of the compartments is not equal, {len} item types in total")]
+    UnequalCompartmentSize { len: usize },
     #[error("the compartments contains multiple shared item types")]
     MultipleSharedItemTypes,
-    #[error("invalid compartment 1: {0}")]
-    Compartment1Error(CompartmentError),
-    #[error("invalid compartment 2: {0}")]
-    Compartment2Error(CompartmentError),
-}
-
-#[derive(Debug, Error)]
-pub enum CompartmentError {
-    #[error("invalid item type {idx}: {err}")]
-    InvalidItemType { idx: usize, err: ItemTypeError },
+    #[error("invalid item type at column {column}: {err}")]
+    InvalidItemType { column: usize, err: ItemTypeError },
 }
 
 #[derive(Debug, Error)]
@@ -135,81 +143,168 @@ pub enum GroupError {
     NoBadge,
     #[error("expected a unique badge")]
     NotUniqueBadge,
-    #[error("expected 3 rucksacks for the group")]
-    NotExactlyRucksacks,
-    #[error("invalid rucksack {idx}: {err}")]
-    InvalidRucksack { idx: usize, err: RucksackError },
+    #[error("expected {expected} rucksacks for the group, found {found}")]
+    NotExactlyRucksacks { expected: usize, found: usize },
+    #[error("invalid rucksack at line {line}: {err}")]
+    InvalidRucksack { line: usize, err: RucksackError },
 }
 
 pub struct Group {
-    badge: ItemType,
-    rucksacks: [Rucksack; 3],
+    badges: ItemSet,
+    rucksacks: Vec<Rucksack>,
 }
 
-impl TryFrom<&[&str]> for Group {
-    type Error = GroupError;
+impl Group {
+    /// Item types carried by every rucksack of the group.
+    pub fn badges(&self) -> ItemSet {
+        self.badges
+    }
 
-    fn try_from(value: &[&str]) -> Result<Self, Self::Error> {
-        let rucksacks: [Rucksack; 3] = value
+    pub fn rucksacks(&self) -> &[Rucksack] {
+        &self.rucksacks
+    }
+
+    /// Parses the rucksacks in `lines`, the first of which is line
+    /// `first_line` of the input.
+    fn parse(lines: &[&str], first_line: usize, format: &InputFormat) -> Result<Self, GroupError> {
+        let rucksacks = lines
             .iter()
             .enumerate()
             .map(|(idx, line)| {
-                line.parse::<Rucksack>()
-                    .map_err(|err| Self::Error::InvalidRucksack { idx, err })
+                Rucksack::parse(line, format).map_err(|err| GroupError::InvalidRucksack {
+                    line: first_line + idx,
+                    err,
+                })
             })
-            .collect::<Result<Vec<_>, _>>()?
-            .try_into()
-            .map_err(|_| Self::Error::NotExactlyRucksacks)?;
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !format.lenient && lines.len() != format.group_size {
+            return Err(GroupError::NotExactlyRucksacks {
+                expected: format.group_size,
+                found: lines.len(),
+            });
+        }
 
         let badges = rucksacks
             .iter()
-            .map(|r| {
-                r.compartments.iter().fold(HashSet::new(), |acc, c| {
-                    acc.union(&c.item_types).cloned().collect()
-                })
-            })
-            .reduce(|acc, set| acc.intersection(&set).cloned().collect())
-            .unwrap();
-
-        if badges.len() > 1 {
-            return Err(GroupError::NotUniqueBadge);
+            .fold(ItemSet::full(), |acc, r| acc & r.item_types());
+
+        if !format.lenient {
+            match badges.len() {
+                0 => return Err(GroupError::NoBadge),
+                1 => {}
+                _ => return Err(GroupError::NotUniqueBadge),
+            }
         }
 
-        let Some(badge) = badges.into_iter().next() else {
-            return Err(GroupError::NoBadge);
-        };
+        Ok(Group { badges, rucksacks })
+    }
+}
+
+impl TryFrom<&[&str]> for Group {
+    type Error = GroupError;
 
-        Ok(Group { badge, rucksacks })
+    fn try_from(value: &[&str]) -> Result<Self, Self::Error> {
+        Self::parse(value, 1, &InputFormat::default())
     }
 }
 
-pub struct Input {
-    groups: Vec<Group>,
+/// How rucksacks are grouped and how strictly the puzzle's rules are held.
+#[derive(Clone, Copy, Debug)]
+pub struct InputFormat {
+    pub group_size: usize,
+    /// Keeps rucksacks without exactly one shared item type, groups without
+    /// exactly one badge and a short last group instead of rejecting them.
+    pub lenient: bool,
 }
 
-#[derive(Debug, Error)]
-pub enum InputError {
-    #[error("invalid group {idx}: {err}")]
-    InvalidGroup { idx: usize, err: GroupError },
+impl Default for InputFormat {
+    fn default() -> Self {
+        Self {
+            group_size: 3,
+            lenient: false,
+        }
+    }
 }
 
-impl FromStr for Input {
-    type Err = InputError;
+impl InputFormat {
+    pub fn parse(&self, s: &str) -> Result<Input, InputError> {
+        if self.group_size == 0 {
+            return Err(InputError::InvalidGroupSize(self.group_size));
+        }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
         let groups = s
             .lines()
             .collect::<Vec<_>>()
-            .chunks(3)
+            .chunks(self.group_size)
             .enumerate()
             .map(|(idx, chunk)| {
-                chunk
-                    .try_into()
-                    .map_err(|err| InputError::InvalidGroup { idx, err })
+                let line = idx * self.group_size + 1;
+
+                Group::parse(chunk, line, self)
+                    .map_err(|err| InputError::InvalidGroup { line, err })
             })
             .collect::<Result<_, _>>()?;
 
-        Ok(Self { groups })
+        Ok(Input { groups })
+    }
+}
+
+pub struct Input {
+    groups: Vec<Group>,
+}
+
+impl Input {
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum InputError {
+    #[error("groups need at least one rucksack, found a group size of {0}")]
+    InvalidGroupSize(usize),
+    #[error("invalid group starting at line {line}: {err}")]
+    InvalidGroup { line: usize, err: GroupError },
+}
+
+impl InputError {
+    /// The 1-based input line at fault: the offending rucksack's when there
+    /// is one, else the first line of the group. There is none when the
+    /// format itself is invalid.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::InvalidGroupSize(_) => None,
+            Self::InvalidGroup {
+                err: GroupError::InvalidRucksack { line, .. },
+                ..
+            }
+            | Self::InvalidGroup { line, .. } => Some(*line),
+        }
+    }
+
+    /// The 1-based column of the offending character, if a single
+    /// character is at fault.
+    pub fn column(&self) -> Option<usize> {
+        match self {
+            Self::InvalidGroup {
+                err:
+                    GroupError::InvalidRucksack {
+                        err: RucksackError::InvalidItemType { column, .. },
+                        ..
+                    },
+                ..
+            } => Some(*column),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Input {
+    type Err = InputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        InputFormat::default().parse(s)
     }
 }
 
@@ -239,4 +334,103 @@ CrZsJsPPZsGzwwsLwLmpwMDw
 
         assert_eq!(answer, 70);
     }
+
+    fn ids(item_types: ItemSet) -> String {
+        item_types.iter().map(|i| i.id()).collect()
+    }
+
+    #[test]
+    fn test_group_size() {
+        let format = InputFormat {
+            group_size: 2,
+            ..Default::default()
+        };
+        let input = format.parse(&INPUT.lines().take(4).collect::<Vec<_>>().join("\n"));
+
+        assert!(input.is_err());
+
+        let input = format.parse("abca\nbdeb\nzyzx\nxwxv\n").unwrap();
+        let badges: Vec<_> = input.groups().iter().map(|g| ids(g.badges())).collect();
+
+        assert_eq!(badges, ["b", "x"]);
+        assert!(InputFormat::default().parse("abca\nbdeb\n").is_err());
+
+        let err = InputFormat {
+            group_size: 0,
+            ..Default::default()
+        }
+        .parse(INPUT)
+        .err()
+        .unwrap();
+
+        assert!(matches!(err, InputError::InvalidGroupSize(0)));
+        assert_eq!((err.line(), err.column()), (None, None));
+    }
+
+    #[test]
+    fn test_lenient() {
+        const INPUT: &str = "abab\nabcc\nbdeb\nfgfh";
+
+        let strict = InputFormat::default();
+        let lenient = InputFormat {
+            lenient: true,
+            ..strict
+        };
+
+        assert!(matches!(
+            strict.parse(INPUT),
+            Err(InputError::InvalidGroup {
+                line: 1,
+                err: GroupError::InvalidRucksack {
+                    line: 1,
+                    err: RucksackError::MultipleSharedItemTypes
+                }
+            })
+        ));
+
+        let input = lenient.parse(INPUT).unwrap();
+        let shared: Vec<_> = input
+            .groups()
+            .iter()
+            .flat_map(Group::rucksacks)
+            .map(|r| ids(r.shared_item_types()))
+            .collect();
+        let badges: Vec<_> = input.groups().iter().map(|g| ids(g.badges())).collect();
+
+        assert_eq!(shared, ["ab", "", "b", "f"]);
+        assert_eq!(badges, ["b", "fgh"]);
+        assert_eq!(part_1(lenient.parse(INPUT).unwrap()), 1 + 2 + 2 + 6);
+        assert_eq!(part_2(lenient.parse(INPUT).unwrap()), 2 + 6 + 7 + 8);
+    }
+
+    #[test]
+    fn test_error_positions() {
+        for (input, line, column, message) in [
+            (
+                "vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg\nwMqvLMZHhHMvwLHjbvcjnnSBnv1QFn\n".to_owned(),
+                4,
+                Some(27),
+                "invalid group starting at line 4: invalid rucksack at line 4: \
+                 invalid item type at column 27: invalid id '1'",
+            ),
+            (
+                "vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSLx\n".to_owned(),
+                2,
+                None,
+                "invalid group starting at line 1: invalid rucksack at line 2: \
+                 the size of the compartments is not equal, 33 item types in total",
+            ),
+            (
+                format!("{INPUT}vJrwpWtwJgWrhcsFMMfFFhFp\n"),
+                7,
+                None,
+                "invalid group starting at line 7: expected 3 rucksacks for the group, found 1",
+            ),
+        ] {
+            let err = input.parse::<Input>().err().unwrap();
+
+            assert_eq!((err.line(), err.column()), (Some(line), column));
+            assert_eq!(err.to_string(), message);
+        }
+    }
 }
@@ -0,0 +1,286 @@
+use crate::{Input, Interval, Section};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+};
+
+/// An elf, numbered in input order so that elves `2n` and `2n + 1` make up
+/// pair `n`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Elf(pub usize);
+
+impl Elf {
+    pub fn pair(self) -> usize {
+        self.0 / 2
+    }
+}
+
+/// Two elves with at least one section in common, the lower numbered first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overlap {
+    pub elves: (Elf, Elf),
+    pub sections: u64,
+}
+
+/// A section together with how many elves were assigned to it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Contested {
+    pub section: Section,
+    pub elves: usize,
+}
+
+/// Looks at the assignments of all pairs at once rather than one pair at a
+/// time.
+pub struct Analysis {
+    assignments: Vec<Interval>,
+}
+
+impl Analysis {
+    pub fn new(input: &Input) -> Self {
+        Self {
+            assignments: input.assignments().collect(),
+        }
+    }
+
+    /// Every two elves whose assignments overlap, ordered by elf.
+    ///
+    /// Sweeps the assignments by start section while keeping the ones still
+    /// open ordered by end section, so the cost grows with the number of
+    /// overlaps found rather than the square of the number of elves.
+    pub fn overlaps(&self) -> Vec<Overlap> {
+        let mut order: Vec<_> = (0..self.assignments.len()).collect();
+        order.sort_unstable_by_key(|&elf| self.assignments[elf].start());
+
+        let mut open: BTreeSet<(Section, usize)> = BTreeSet::new();
+        let mut overlaps = vec![];
+
+        for elf in order {
+            let assignment = self.assignments[elf];
+
+            while open
+                .first()
+                .is_some_and(|&(end, _)| end < assignment.start())
+            {
+                open.pop_first();
+            }
+
+            overlaps.extend(open.iter().map(|&(_, other)| {
+                let shared = self.assignments[other]
+                    .intersection(&assignment)
+                    .expect("open assignments reach the current start");
+
+                Overlap {
+                    elves: (Elf(elf.min(other)), Elf(elf.max(other))),
+                    sections: shared.length(),
+                }
+            }));
+
+            open.insert((assignment.end(), elf));
+        }
+
+        overlaps.sort_unstable_by_key(|overlap| overlap.elves);
+        overlaps
+    }
+
+    /// Overlaps between elves that belong to different pairs.
+    pub fn cross_pair_overlaps(&self) -> Vec<Overlap> {
+        let mut overlaps = self.overlaps();
+        overlaps.retain(|Overlap { elves: (a, b), .. }| a.pair() != b.pair());
+        overlaps
+    }
+
+    /// The section assigned to the most elves, the lowest one on a tie.
+    pub fn most_contested(&self) -> Option<Contested> {
+        // Each assignment opens at its start and closes just past its end.
+        let mut events: Vec<(u64, i64)> = self
+            .assignments
+            .iter()
+            .flat_map(|a| [(u64::from(a.start()), 1), (u64::from(a.end()) + 1, -1)])
+            .collect();
+        events.sort_unstable();
+
+        let mut most: Option<Contested> = None;
+        let mut elves = 0;
+
+        for (idx, &(section, change)) in events.iter().enumerate() {
+            elves += change;
+
+            if events
+                .get(idx + 1)
+                .is_some_and(|&(next, _)| next == section)
+            {
+                continue;
+            }
+
+            let elves = elves as usize;
+
+            if elves > 0 && most.is_none_or(|most| elves > most.elves) {
+                most = Some(Contested {
+                    section: section as Section,
+                    elves,
+                });
+            }
+        }
+
+        most
+    }
+
+    pub fn overlap_matrix(&self) -> OverlapMatrix {
+        OverlapMatrix {
+            lengths: self.assignments.iter().map(Interval::length).collect(),
+            shared: self
+                .overlaps()
+                .into_iter()
+                .map(|overlap| (overlap.elves, overlap.sections))
+                .collect(),
+        }
+    }
+}
+
+/// Number of sections every two elves share, with each elf's own
+/// assignment length on the diagonal. Only overlapping elves are stored.
+pub struct OverlapMatrix {
+    lengths: Vec<u64>,
+    shared: HashMap<(Elf, Elf), u64>,
+}
+
+impl OverlapMatrix {
+    /// Number of elves along each side.
+    pub fn size(&self) -> usize {
+        self.lengths.len()
+    }
+
+    pub fn get(&self, a: Elf, b: Elf) -> u64 {
+        if a == b {
+            return self.lengths[a.0];
+        }
+
+        self.shared.get(&(a.min(b), a.max(b))).copied().unwrap_or(0)
+    }
+}
+
+impl fmt::Display for OverlapMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .lengths
+            .iter()
+            .max()
+            .map_or(1, |max| max.to_string().len());
+
+        for a in 0..self.size() {
+            let row: Vec<_> = (0..self.size())
+                .map(|b| format!("{:>width$}", self.get(Elf(a), Elf(b))))
+                .collect();
+
+            writeln!(f, "{}", row.join(" "))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::INPUT;
+
+    fn brute_force(analysis: &Analysis) -> Vec<Overlap> {
+        let assignments = &analysis.assignments;
+        let mut overlaps = vec![];
+
+        for a in 0..assignments.len() {
+            for b in a + 1..assignments.len() {
+                if let Some(shared) = assignments[a].intersection(&assignments[b]) {
+                    overlaps.push(Overlap {
+                        elves: (Elf(a), Elf(b)),
+                        sections: shared.length(),
+                    });
+                }
+            }
+        }
+
+        overlaps
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let analysis = Analysis::new(&INPUT.parse().unwrap());
+        let overlaps = analysis.overlaps();
+
+        assert_eq!(overlaps, brute_force(&analysis));
+        assert_eq!(
+            overlaps[..3],
+            [
+                Overlap {
+                    elves: (Elf(0), Elf(2)),
+                    sections: 2,
+                },
+                Overlap {
+                    elves: (Elf(0), Elf(3)),
+                    sections: 1,
+                },
+                Overlap {
+                    elves: (Elf(0), Elf(6)),
+                    sections: 3,
+                },
+            ]
+        );
+
+        let cross_pair = analysis.cross_pair_overlaps();
+
+        assert!(cross_pair
+            .iter()
+            .all(|o| o.elves.0.pair() != o.elves.1.pair()));
+        assert_eq!(overlaps.len() - cross_pair.len(), 4);
+    }
+
+    #[test]
+    fn test_most_contested() {
+        let analysis = Analysis::new(&INPUT.parse().unwrap());
+
+        assert_eq!(
+            analysis.most_contested(),
+            Some(Contested {
+                section: 6,
+                elves: 8
+            })
+        );
+        assert_eq!(Analysis::new(&"".parse().unwrap()).most_contested(), None);
+    }
+
+    #[test]
+    fn test_overlap_matrix() {
+        let matrix = Analysis::new(&"2-4,6-8\n2-3,4-5\n".parse().unwrap()).overlap_matrix();
+
+        assert_eq!(matrix.size(), 4);
+        assert_eq!(matrix.get(Elf(3), Elf(0)), 1);
+        assert_eq!(matrix.get(Elf(1), Elf(1)), 3);
+        assert_eq!(
+            matrix.to_string(),
+            "\
+3 0 2 1
+0 3 0 0
+2 0 2 0
+1 0 0 2
+"
+        );
+    }
+
+    #[test]
+    fn test_many_assignments() {
+        // Assignments laid out like roof tiles, each overlapping the next few.
+        let input: String = (0..100_000u32)
+            .map(|i| format!("{}-{},{}-{}\n", i * 4, i * 4 + 9, i * 4 + 2, i * 4 + 3))
+            .collect();
+        let analysis = Analysis::new(&input.parse().unwrap());
+
+        assert_eq!(analysis.overlaps().len(), 399_996);
+        assert_eq!(
+            analysis.most_contested(),
+            Some(Contested {
+                section: 6,
+                elves: 3
+            })
+        );
+    }
+}
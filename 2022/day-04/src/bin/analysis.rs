@@ -0,0 +1,23 @@
+use color_eyre::Result;
+use day_04::{Analysis, Input};
+use std::fs;
+
+fn main() -> Result<()> {
+    let input: Input = fs::read_to_string("./input.txt")?.parse()?;
+    let analysis = Analysis::new(&input);
+
+    println!("sections covered: {}", input.sections_covered());
+    println!(
+        "overlaps across pairs: {}",
+        analysis.cross_pair_overlaps().len()
+    );
+
+    if let Some(contested) = analysis.most_contested() {
+        println!(
+            "most contested section: {} ({} elves)",
+            contested.section, contested.elves
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,300 @@
+use std::fmt;
+
+pub type Section = u32;
+
+/// A non-empty, inclusive range of sections.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    start: Section,
+    end: Section,
+}
+
+impl Interval {
+    /// The sections from `start` to `end` inclusive, or `None` when `start`
+    /// comes after `end`.
+    pub fn new(start: Section, end: Section) -> Option<Self> {
+        (start <= end).then_some(Self { start, end })
+    }
+
+    pub fn start(&self) -> Section {
+        self.start
+    }
+
+    pub fn end(&self) -> Section {
+        self.end
+    }
+
+    /// Number of sections covered.
+    pub fn length(&self) -> u64 {
+        u64::from(self.end - self.start) + 1
+    }
+
+    pub fn contains(&self, section: Section) -> bool {
+        self.start <= section && section <= self.end
+    }
+
+    pub fn fully_contains(&self, other: &Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    pub fn union(&self, other: &Self) -> IntervalSet {
+        [*self, *other].into_iter().collect()
+    }
+
+    pub fn difference(&self, other: &Self) -> IntervalSet {
+        let mut set = IntervalSet::from(*self);
+        set.remove(*other);
+        set
+    }
+
+    /// Whether the two intervals leave no section between them, so their
+    /// union is a single interval.
+    fn touches(&self, other: &Self) -> bool {
+        self.start <= other.end.saturating_add(1) && other.start <= self.end.saturating_add(1)
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// A set of sections, kept as sorted intervals that neither overlap nor touch.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of sections covered.
+    pub fn length(&self) -> u64 {
+        self.intervals.iter().map(Interval::length).sum()
+    }
+
+    pub fn contains(&self, section: Section) -> bool {
+        let idx = self.intervals.partition_point(|i| i.end < section);
+
+        self.intervals.get(idx).is_some_and(|i| i.contains(section))
+    }
+
+    /// Adds the sections of `interval`, merging it with the intervals it
+    /// overlaps or touches.
+    pub fn insert(&mut self, interval: Interval) {
+        let from = self
+            .intervals
+            .partition_point(|i| i.end.saturating_add(1) < interval.start);
+        let to = from
+            + self.intervals[from..]
+                .iter()
+                .take_while(|i| i.touches(&interval))
+                .count();
+
+        let merged = self.intervals[from..to]
+            .iter()
+            .fold(interval, |acc, i| Interval {
+                start: acc.start.min(i.start),
+                end: acc.end.max(i.end),
+            });
+
+        self.intervals.splice(from..to, [merged]);
+    }
+
+    /// Takes the sections of `interval` out of the set.
+    pub fn remove(&mut self, interval: Interval) {
+        let from = self.intervals.partition_point(|i| i.end < interval.start);
+        let to = from
+            + self.intervals[from..]
+                .iter()
+                .take_while(|i| i.overlaps(&interval))
+                .count();
+
+        let left = self.intervals[from..to]
+            .first()
+            .and_then(|i| Interval::new(i.start, interval.start.checked_sub(1)?));
+        let right = self.intervals[from..to]
+            .last()
+            .and_then(|i| Interval::new(interval.end.checked_add(1)?, i.end));
+
+        self.intervals
+            .splice(from..to, left.into_iter().chain(right));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.intervals
+            .iter()
+            .chain(&other.intervals)
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = vec![];
+        let (mut a, mut b) = (
+            self.intervals.iter().peekable(),
+            other.intervals.iter().peekable(),
+        );
+
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            intervals.extend(x.intersection(y));
+
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+
+        Self { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut set = self.clone();
+
+        for &interval in &other.intervals {
+            set.remove(interval);
+        }
+
+        set
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> Self {
+        Self {
+            intervals: vec![interval],
+        }
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = Interval>>(iter: T) -> Self {
+        let mut intervals: Vec<_> = iter.into_iter().collect();
+        intervals.sort_unstable();
+
+        let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if last.touches(&interval) => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+
+        Self { intervals: merged }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(start: Section, end: Section) -> Interval {
+        Interval::new(start, end).unwrap()
+    }
+
+    fn set(intervals: &[(Section, Section)]) -> IntervalSet {
+        intervals.iter().map(|&(s, e)| interval(s, e)).collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        assert_eq!(Interval::new(8, 2), None);
+        assert_eq!(interval(2, 4).length(), 3);
+        assert_eq!(interval(6, 6).length(), 1);
+        assert_eq!(interval(0, Section::MAX).length(), 1 << 32);
+        assert_eq!(interval(2, 8).to_string(), "2-8");
+    }
+
+    #[test]
+    fn test_interval_operations() {
+        assert_eq!(
+            interval(2, 6).intersection(&interval(4, 8)),
+            Some(interval(4, 6))
+        );
+        assert_eq!(interval(2, 4).intersection(&interval(6, 8)), None);
+
+        assert_eq!(interval(2, 4).union(&interval(5, 8)), set(&[(2, 8)]));
+        assert_eq!(
+            interval(2, 4).union(&interval(6, 8)),
+            set(&[(2, 4), (6, 8)])
+        );
+
+        assert_eq!(
+            interval(2, 8).difference(&interval(3, 7)),
+            set(&[(2, 2), (8, 8)])
+        );
+        assert_eq!(
+            interval(2, 8).difference(&interval(0, 9)),
+            IntervalSet::new()
+        );
+        assert_eq!(interval(2, 8).difference(&interval(5, 9)), set(&[(2, 4)]));
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut intervals = IntervalSet::new();
+
+        for (start, end, expected) in [
+            (10, 12, vec![(10, 12)]),
+            (2, 4, vec![(2, 4), (10, 12)]),
+            (6, 6, vec![(2, 4), (6, 6), (10, 12)]),
+            (5, 5, vec![(2, 6), (10, 12)]),
+            (8, 20, vec![(2, 6), (8, 20)]),
+            (0, Section::MAX, vec![(0, Section::MAX)]),
+        ] {
+            intervals.insert(interval(start, end));
+
+            assert_eq!(intervals, set(&expected));
+        }
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut intervals = set(&[(2, 6), (8, 20)]);
+
+        for (start, end, expected) in [
+            (0, 1, vec![(2, 6), (8, 20)]),
+            (4, 4, vec![(2, 3), (5, 6), (8, 20)]),
+            (6, 10, vec![(2, 3), (5, 5), (11, 20)]),
+            (0, 5, vec![(11, 20)]),
+            (20, Section::MAX, vec![(11, 19)]),
+        ] {
+            intervals.remove(interval(start, end));
+
+            assert_eq!(intervals, set(&expected));
+        }
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(2, 4), (6, 8), (12, 14)]);
+        let b = set(&[(3, 7), (14, 20)]);
+
+        assert_eq!(a.union(&b), set(&[(2, 8), (12, 20)]));
+        assert_eq!(a.intersection(&b), set(&[(3, 4), (6, 7), (14, 14)]));
+        assert_eq!(a.difference(&b), set(&[(2, 2), (8, 8), (12, 13)]));
+        assert_eq!(a.length(), 9);
+        assert!(a.contains(7) && !a.contains(5) && !a.contains(15));
+    }
+}
@@ -1,3 +1,8 @@
+mod analysis;
+mod interval;
+
+pub use analysis::{Analysis, Contested, Elf, Overlap, OverlapMatrix};
+pub use interval::{Interval, IntervalSet, Section};
 use std::{num::ParseIntError, str::FromStr};
 use thiserror::Error;
 
@@ -19,8 +24,6 @@ pub fn part_2(input: Input) -> usize {
         .count()
 }
 
-type Section = u32;
-
 #[derive(Debug, Error)]
 pub enum SectionRangeError {
     #[error("expected dash separated sections")]
@@ -29,22 +32,20 @@ pub enum SectionRangeError {
     StartSectionError(#[source] ParseIntError),
     #[error("invalid end section")]
     EndSectionError(#[source] ParseIntError),
+    #[error("start section {start} comes after end section {end}")]
+    Reversed { start: Section, end: Section },
 }
 
-struct SectionRange {
-    start: Section,
-    end: Section,
-}
+#[derive(Debug)]
+struct SectionRange(Interval);
 
 impl SectionRange {
     fn fully_contains(&self, other: &Self) -> bool {
-        self.start <= other.start && self.end >= other.end
+        self.0.fully_contains(&other.0)
     }
 
     fn overlap(&self, other: &Self) -> bool {
-        self.start <= other.start && self.end >= other.start
-            || self.start <= other.end && self.end >= other.end
-            || other.fully_contains(self)
+        self.0.overlaps(&other.0)
     }
 }
 
@@ -53,11 +54,12 @@ impl FromStr for SectionRange {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (start, end) = s.split_once('-').ok_or(Self::Err::DashNotFound)?;
+        let start: Section = start.parse().map_err(Self::Err::StartSectionError)?;
+        let end: Section = end.parse().map_err(Self::Err::EndSectionError)?;
 
-        Ok(Self {
-            start: start.parse().map_err(Self::Err::StartSectionError)?,
-            end: end.parse().map_err(Self::Err::EndSectionError)?,
-        })
+        Interval::new(start, end)
+            .map(Self)
+            .ok_or(Self::Err::Reversed { start, end })
     }
 }
 
@@ -101,6 +103,25 @@ pub struct Input {
     section_range_pairs: Vec<SectionRangePair>,
 }
 
+impl Input {
+    /// Each elf's assignment, both elves of a pair in turn.
+    pub fn assignments(&self) -> impl Iterator<Item = Interval> + '_ {
+        self.section_range_pairs
+            .iter()
+            .flat_map(|SectionRangePair(range_1, range_2)| [range_1.0, range_2.0])
+    }
+
+    /// Every section assigned to at least one elf.
+    pub fn coverage(&self) -> IntervalSet {
+        self.assignments().collect()
+    }
+
+    /// Number of sections assigned to at least one elf.
+    pub fn sections_covered(&self) -> u64 {
+        self.coverage().length()
+    }
+}
+
 impl FromStr for Input {
     type Err = InputError;
 
@@ -121,8 +142,9 @@ impl FromStr for Input {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
-    const INPUT: &str = "\
+    pub(crate) const INPUT: &str = "\
 2-4,6-8
 2-3,4-5
 5-7,7-9
@@ -144,4 +166,64 @@ mod tests {
 
         assert_eq!(answer, 4);
     }
+
+    #[test]
+    fn test_sections_covered() {
+        let input: Input = INPUT.parse().unwrap();
+
+        assert_eq!(input.coverage().intervals(), [Interval::new(2, 9).unwrap()]);
+        assert_eq!(input.sections_covered(), 8);
+    }
+
+    #[test]
+    fn test_assignments() {
+        let input: Input = INPUT.parse().unwrap();
+        let assignments: Vec<_> = input.assignments().map(|i| i.to_string()).collect();
+
+        assert_eq!(assignments[..4], ["2-4", "6-8", "2-3", "4-5"]);
+        assert_eq!(assignments.len(), 12);
+    }
+
+    #[test]
+    fn test_reversed_section_range() {
+        assert!(matches!(
+            "8-2".parse::<SectionRange>(),
+            Err(SectionRangeError::Reversed { start: 8, end: 2 })
+        ));
+        assert!(matches!(
+            "2-8,8-2".parse::<SectionRangePair>(),
+            Err(SectionRangePairError::SecondSectionRangeError(
+                SectionRangeError::Reversed { .. }
+            ))
+        ));
+        assert!("6-6".parse::<SectionRange>().is_ok());
+    }
+
+    fn section_range() -> impl Strategy<Value = SectionRange> {
+        (0..20u32, 0..20u32)
+            .prop_map(|(start, length)| SectionRange(Interval::new(start, start + length).unwrap()))
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_section_range(start in 0..100u32, end in 0..100u32) {
+            let parsed = format!("{start}-{end}").parse::<SectionRange>();
+
+            prop_assert_eq!(parsed.is_ok(), start <= end);
+        }
+
+        #[test]
+        fn test_overlap(a in section_range(), b in section_range()) {
+            let shared = (0..40).any(|section| a.0.contains(section) && b.0.contains(section));
+
+            prop_assert_eq!(a.overlap(&b), b.overlap(&a));
+            prop_assert_eq!(a.overlap(&b), shared);
+
+            if a.fully_contains(&b) || b.fully_contains(&a) {
+                prop_assert!(a.overlap(&b));
+            }
+
+            prop_assert_eq!(a.fully_contains(&b) && b.fully_contains(&a), a.0 == b.0);
+        }
+    }
 }
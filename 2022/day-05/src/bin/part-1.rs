@@ -5,7 +5,7 @@ use std::fs;
 fn main() -> Result<()> {
     let input = fs::read_to_string("./input.txt")?;
 
-    println!("{}", part_1(input.parse()?));
+    println!("{}", part_1(input.parse()?)?);
 
     Ok(())
 }
@@ -0,0 +1,23 @@
+use color_eyre::{eyre::bail, Result};
+use day_05::{CrateMover9000, CrateMover9001, Drawing, Procedure};
+use std::{env, fs};
+
+/// Usage: `replay [9000|9001]`
+///
+/// Prints the stacks before the first step and after every step.
+fn main() -> Result<()> {
+    let input = fs::read_to_string("./input.txt")?;
+    let mut procedure: Procedure = input.parse()?;
+
+    println!("{}\n", Drawing(&procedure.stacks));
+
+    let print = |step: &_, stacks: &_| println!("{step}\n\n{}\n", Drawing(stacks));
+
+    match env::args().nth(1).as_deref() {
+        None | Some("9000") => procedure.replay(&CrateMover9000, print)?,
+        Some("9001") => procedure.replay(&CrateMover9001, print)?,
+        Some(model) => bail!("expected crane model 9000 or 9001, found: {model:?}"),
+    }
+
+    Ok(())
+}
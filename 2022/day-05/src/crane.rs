@@ -0,0 +1,95 @@
+use crate::procedure::Stack;
+use std::num::NonZeroUsize;
+
+/// A crane model, deciding how crates travel between two stacks.
+pub trait Crane {
+    /// Moves `quantity` crates from the top of `from` to the top of `to`.
+    fn lift(&self, quantity: usize, from: &mut Stack, to: &mut Stack);
+}
+
+/// Moves crates one at a time, reversing their order.
+#[derive(Clone, Copy, Debug)]
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn lift(&self, quantity: usize, from: &mut Stack, to: &mut Stack) {
+        for _ in 0..quantity {
+            let Some(crate_) = from.pop() else {
+                break;
+            };
+
+            to.push(crate_);
+        }
+    }
+}
+
+/// Moves all crates at once, keeping their order.
+#[derive(Clone, Copy, Debug)]
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn lift(&self, quantity: usize, from: &mut Stack, to: &mut Stack) {
+        let at = from.len().saturating_sub(quantity);
+        to.extend(from.drain(at..));
+    }
+}
+
+/// Moves up to `capacity` crates at a time, keeping their order within each
+/// lift.
+#[derive(Clone, Copy, Debug)]
+pub struct BoundedCrane {
+    pub capacity: NonZeroUsize,
+}
+
+impl BoundedCrane {
+    /// A crane lifting up to `capacity` crates, or `None` if it could not
+    /// lift any.
+    pub fn new(capacity: usize) -> Option<Self> {
+        NonZeroUsize::new(capacity).map(|capacity| Self { capacity })
+    }
+}
+
+impl Crane for BoundedCrane {
+    fn lift(&self, quantity: usize, from: &mut Stack, to: &mut Stack) {
+        let mut remaining = quantity.min(from.len());
+
+        while remaining > 0 {
+            let load = remaining.min(self.capacity.get());
+            CrateMover9001.lift(load, from, to);
+            remaining -= load;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::procedure::Crate;
+
+    fn lift(crane: impl Crane, quantity: usize) -> (String, String) {
+        let mut from = "ABCDE".chars().map(|c| Crate(c.into())).collect();
+        let mut to = vec![Crate('Z'.into())];
+
+        crane.lift(quantity, &mut from, &mut to);
+
+        let letters = |stack: Stack| stack.into_iter().map(|c| c.0).collect();
+
+        (letters(from), letters(to))
+    }
+
+    #[test]
+    fn test_lift() {
+        assert_eq!(lift(CrateMover9000, 3), ("AB".into(), "ZEDC".into()));
+        assert_eq!(lift(CrateMover9001, 3), ("AB".into(), "ZCDE".into()));
+        assert_eq!(
+            lift(BoundedCrane::new(2).unwrap(), 5),
+            ("".into(), "ZDEBCA".into())
+        );
+        assert_eq!(
+            lift(BoundedCrane::new(1).unwrap(), 3),
+            lift(CrateMover9000, 3)
+        );
+        assert!(BoundedCrane::new(0).is_none());
+        assert_eq!(lift(CrateMover9001, 9), ("".into(), "ZABCDE".into()));
+    }
+}
@@ -1,58 +1,18 @@
+mod crane;
 mod parser;
 mod procedure;
 
-use procedure::Procedure;
+pub use crane::{BoundedCrane, Crane, CrateMover9000, CrateMover9001};
+pub use procedure::{Crate, Drawing, ExecuteError, Procedure, Stack, Step, StepError};
 
-pub fn part_1(procedure: Procedure) -> String {
-    let mut stacks = procedure.stacks;
-
-    for step in procedure.steps {
-        for _ in 0..step.crates_quantity {
-            let Some(target_stack) = stacks.get_mut((step.target_stack - 1) as usize) else {
-                break;
-            };
-
-            let Some(crate_) = target_stack.pop() else {
-                break;
-            };
-
-            let Some(destination_stack) = stacks.get_mut((step.destination_stack - 1) as usize) else {
-                break;
-            };
-
-            destination_stack.push(crate_);
-        }
-    }
-
-    stacks
-        .iter()
-        .map(|s| s.last().map(|c| c.0).unwrap_or(' '))
-        .collect()
+pub fn part_1(mut procedure: Procedure) -> Result<String, ExecuteError> {
+    procedure.execute(&CrateMover9000)?;
+    Ok(procedure.top_crates())
 }
 
-pub fn part_2(procedure: Procedure) -> String {
-    let mut stacks = procedure.stacks;
-
-    for step in procedure.steps {
-        let Some(target_stack) = stacks.get_mut((step.target_stack - 1) as usize) else {
-            break;
-        };
-
-        let mut crates = target_stack
-            .drain((target_stack.len() - step.crates_quantity as usize)..)
-            .collect::<Vec<_>>();
-
-        let Some(destination_stack) = stacks.get_mut((step.destination_stack - 1) as usize) else {
-            break;
-        };
-
-        destination_stack.append(&mut crates);
-    }
-
-    stacks
-        .iter()
-        .map(|s| s.last().map(|c| c.0).unwrap_or(' '))
-        .collect()
+pub fn part_2(mut procedure: Procedure) -> Result<String, ExecuteError> {
+    procedure.execute(&CrateMover9001)?;
+    Ok(procedure.top_crates())
 }
 
 #[cfg(test)]
@@ -74,13 +34,88 @@ move 1 from 1 to 2
     fn test_part_1() {
         let answer = part_1(PROCEDURE.parse().unwrap());
 
-        assert_eq!(answer, "CMZ".to_owned());
+        assert_eq!(answer, Ok("CMZ".to_owned()));
     }
 
     #[test]
     fn test_part_2() {
         let answer = part_2(PROCEDURE.parse().unwrap());
 
-        assert_eq!(answer, "MCD".to_owned());
+        assert_eq!(answer, Ok("MCD".to_owned()));
+    }
+
+    #[test]
+    fn test_drawing() {
+        let mut procedure: Procedure = PROCEDURE.parse().unwrap();
+        let (drawing, _steps) = PROCEDURE.split_once("\n\n").unwrap();
+
+        assert_eq!(Drawing(&procedure.stacks).to_string(), drawing);
+
+        let mut drawings = vec![];
+
+        procedure
+            .replay(&CrateMover9000, |step, stacks| {
+                drawings.push(format!("{step}\n{}", Drawing(stacks)));
+            })
+            .unwrap();
+
+        assert_eq!(
+            drawings.last().unwrap(),
+            "\
+move 1 from 1 to 2
+        [Z]
+        [N]
+        [D]
+[C] [M] [P]
+ 1   2   3 "
+        );
+    }
+
+    #[test]
+    fn test_wide_drawing() {
+        let stacks: Vec<Stack> = (1..=10)
+            .map(|n| (0..n % 3).map(|i| Crate(format!("c{n}.{i}"))).collect())
+            .collect();
+        let drawing = Drawing(&stacks).to_string();
+        let procedure: Procedure = format!("{drawing}\n\nmove 1 from 10 to 1").parse().unwrap();
+
+        assert!(drawing.ends_with("   9      10   "));
+        assert_eq!(procedure.stacks, stacks);
+    }
+
+    #[test]
+    fn test_invalid_steps() {
+        for (steps, err) in [
+            (
+                "move 1 from 2 to 1\nmove 1 from 4 to 1",
+                ExecuteError::InvalidStep {
+                    step: 2,
+                    err: StepError::NoSuchStack(4),
+                },
+            ),
+            (
+                "move 1 from 0 to 1",
+                ExecuteError::InvalidStep {
+                    step: 1,
+                    err: StepError::NoSuchStack(0),
+                },
+            ),
+            (
+                "move 4 from 2 to 1",
+                ExecuteError::InvalidStep {
+                    step: 1,
+                    err: StepError::NotEnoughCrates {
+                        stack: 2,
+                        requested: 4,
+                        available: 3,
+                    },
+                },
+            ),
+        ] {
+            let drawing = PROCEDURE.split("\n\n").next().unwrap();
+            let mut procedure: Procedure = format!("{drawing}\n\n{steps}").parse().unwrap();
+
+            assert_eq!(procedure.execute(&CrateMover9001), Err(err));
+        }
     }
 }
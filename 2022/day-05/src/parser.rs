@@ -1,14 +1,15 @@
 use crate::procedure::{Crate, Procedure, Stack, Step};
-use nom::branch::alt;
-use nom::combinator::{value, verify};
-use nom::multi::separated_list0;
-use nom::sequence::{delimited, preceded, separated_pair, terminated, tuple};
+use nom::combinator::{consumed, verify};
+use nom::error::{Error, ErrorKind};
+use nom::multi::{many0, separated_list0};
+use nom::sequence::{delimited, pair, preceded, separated_pair, terminated, tuple};
 use nom::IResult;
 use nom::{
-    bytes::complete::tag,
-    character::complete::{anychar, char, u32},
+    bytes::complete::{tag, take_while1},
+    character::complete::{char, space0, u32},
     combinator::map,
 };
+use std::ops::Range;
 
 fn parse_step(input: &str) -> IResult<&str, Step> {
     let (input, (crates_quantity, target_stack, destination_stack)) = tuple((
@@ -35,55 +36,84 @@ fn parse_steps(input: &str) -> IResult<&str, Steps> {
 
 fn parse_crate(input: &str) -> IResult<&str, Crate> {
     map(
-        delimited(char('['), verify(anychar, |c| c.is_uppercase()), char(']')),
-        Crate,
+        delimited(
+            char('['),
+            take_while1(|c: char| c != ']' && !c.is_whitespace() && !c.is_control()),
+            char(']'),
+        ),
+        |id: &str| Crate(id.to_owned()),
     )(input)
 }
 
-type Layer = Vec<Option<Crate>>;
+/// Character columns covered by a cell of the drawing, end exclusive.
+type Span = Range<usize>;
+
+/// Splits a line into its cells, tracking the columns each one covers.
+fn spanned<'a, O>(
+    cell: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<(Span, O)>> {
+    map(
+        terminated(many0(pair(space0, consumed(cell))), space0),
+        |cells| {
+            let mut column = 0;
+
+            cells
+                .into_iter()
+                .map(|(spaces, (raw, output)): (&str, (&str, O))| {
+                    column += spaces.chars().count();
+                    let span = column..column + raw.chars().count();
+                    column = span.end;
+                    (span, output)
+                })
+                .collect()
+        },
+    )
+}
+
+type Layer = Vec<(Span, Crate)>;
 
 fn parse_layer(input: &str) -> IResult<&str, Layer> {
-    separated_list0(
-        char(' '),
-        alt((value(None, tag("   ")), map(parse_crate, Some))),
-    )(input)
+    spanned(parse_crate)(input)
 }
 
 type Layers = Vec<Layer>;
 
 fn parse_layers(input: &str) -> IResult<&str, Layers> {
-    separated_list0(char('\n'), parse_layer)(input)
-}
-
-fn parse_stack_number(input: &str) -> IResult<&str, u32> {
-    delimited(char(' '), u32, char(' '))(input)
+    many0(terminated(parse_layer, char('\n')))(input)
 }
 
-fn parse_stack_numbers(input: &str) -> IResult<&str, Vec<u32>> {
-    separated_list0(char(' '), parse_stack_number)(input)
+fn parse_stack_numbers(input: &str) -> IResult<&str, Vec<Span>> {
+    map(
+        verify(spanned(u32), |labels: &Vec<(Span, u32)>| {
+            !labels.is_empty() && labels.iter().zip(1..).all(|((_, n), i)| *n == i)
+        }),
+        |labels| labels.into_iter().map(|(span, _)| span).collect(),
+    )(input)
 }
 
+/// Parses the drawing, placing each crate on the stack whose label in the
+/// footer shares a column with it.
 fn parse_stacks(input: &str) -> IResult<&str, Vec<Stack>> {
-    let (input, stack_layers) = terminated(parse_layers, parse_stack_numbers)(input)?;
+    let (rest, (layers, labels)) = pair(parse_layers, parse_stack_numbers)(input)?;
 
-    let mut stacks: Vec<Stack> = vec![];
-
-    for stack_layer in stack_layers.into_iter().rev() {
-        for (j, crate_option) in stack_layer.into_iter().enumerate() {
-            if stacks.len() <= j {
-                let stack = vec![];
-                stacks.push(stack);
-            }
+    let mut stacks: Vec<Stack> = vec![vec![]; labels.len()];
 
-            let stack = &mut stacks[j];
+    for layer in layers.into_iter().rev() {
+        for (span, crate_) in layer {
+            let mut below = labels
+                .iter()
+                .enumerate()
+                .filter(|(_, label)| label.start < span.end && span.start < label.end)
+                .map(|(j, _)| j);
 
-            if let Some(crate_) = crate_option {
-                stack.push(crate_);
+            match (below.next(), below.next()) {
+                (Some(j), None) => stacks[j].push(crate_),
+                _ => return Err(nom::Err::Error(Error::new(input, ErrorKind::Verify))),
             }
         }
     }
 
-    Ok((input, stacks))
+    Ok((rest, stacks))
 }
 
 pub fn parse_procedure(input: &str) -> IResult<&str, Procedure> {
@@ -140,9 +170,17 @@ move 3 from 1 to 3";
 
     #[test]
     fn test_parse_crate() {
-        const CRATE: &str = "[A]";
+        for (input, id) in [
+            ("[A]", "A"),
+            ("[z]", "z"),
+            ("[7]", "7"),
+            ("[AB-12]", "AB-12"),
+        ] {
+            assert_eq!(parse_crate(input), Ok(("", Crate(id.to_owned()))));
+        }
 
-        assert_eq!(parse_crate(CRATE), Ok(("", Crate('A'))));
+        assert!(parse_crate("[]").is_err());
+        assert!(parse_crate("[A B]").is_err());
     }
 
     #[test]
@@ -151,39 +189,41 @@ move 3 from 1 to 3";
 
         assert_eq!(
             parse_layer(LAYER),
-            Ok(("", vec![None, Some(Crate('D')), None]))
+            Ok(("", vec![(4..7, Crate('D'.into()))]))
         );
     }
 
     #[test]
     fn test_parse_layers() {
         const LAYERS: &str = "    [D]    
-[N] [C]    ";
+[N] [C]    
+";
 
         assert_eq!(
             parse_layers(LAYERS),
             Ok((
                 "",
                 vec![
-                    vec![None, Some(Crate('D')), None],
-                    vec![Some(Crate('N')), Some(Crate('C')), None]
+                    vec![(4..7, Crate('D'.into()))],
+                    vec![(0..3, Crate('N'.into())), (4..7, Crate('C'.into()))]
                 ]
             ))
         );
     }
 
-    #[test]
-    fn test_parse_stack_number() {
-        const STACK_NUMBER: &str = " 1 ";
-
-        assert_eq!(parse_stack_number(STACK_NUMBER), Ok(("", 1)));
-    }
-
     #[test]
     fn test_parse_stack_numbers() {
-        const STACK_NUMBERS: &str = " 1   2   3 ";
-
-        assert_eq!(parse_stack_numbers(STACK_NUMBERS), Ok(("", vec![1, 2, 3])));
+        assert_eq!(
+            parse_stack_numbers(" 1   2   3 "),
+            Ok(("", vec![1..2, 5..6, 9..10]))
+        );
+        assert_eq!(
+            parse_stack_numbers(" 1   2   3   4   5   6   7   8   9   10 ")
+                .map(|(rest, spans)| (rest, spans[8..].to_vec())),
+            Ok(("", vec![33..34, 37..39]))
+        );
+        assert!(parse_stack_numbers(" 1   3 ").is_err());
+        assert!(parse_stack_numbers("   ").is_err());
     }
 
     #[test]
@@ -198,14 +238,36 @@ move 3 from 1 to 3";
             Ok((
                 "",
                 vec![
-                    vec![Crate('Z'), Crate('N')],
-                    vec![Crate('M'), Crate('C'), Crate('D')],
-                    vec![Crate('P')],
+                    vec![Crate('Z'.into()), Crate('N'.into())],
+                    vec![Crate('M'.into()), Crate('C'.into()), Crate('D'.into())],
+                    vec![Crate('P'.into())],
                 ]
             ))
         );
     }
 
+    #[test]
+    fn test_parse_wide_stacks() {
+        const INPUT: &str = r#"                                         [x1]
+[A] [B] [C] [D] [E] [F] [G] [H] [I] [J]  [k]
+ 1   2   3   4   5   6   7   8   9   10   11 "#;
+
+        let (rest, stacks) = parse_stacks(INPUT).unwrap();
+
+        assert_eq!(rest, "");
+        assert_eq!(stacks.len(), 11);
+        assert_eq!(stacks[9], vec![Crate('J'.into())]);
+        assert_eq!(stacks[10], vec![Crate('k'.into()), Crate("x1".into())]);
+    }
+
+    #[test]
+    fn test_parse_misaligned_stacks() {
+        // The crate sits between the labels of stacks 1 and 2.
+        assert!(parse_stacks("   [A]   \n 1       2 ").is_err());
+        // The crate reaches across both labels.
+        assert!(parse_stacks("[ABCDE]\n 1 2 ").is_err());
+    }
+
     #[test]
     fn test_parse_procedure() {
         const PROCEDURE: &str = "    [D]    
@@ -224,9 +286,9 @@ move 1 from 1 to 2";
                 "",
                 Procedure {
                     stacks: vec![
-                        vec![Crate('Z'), Crate('N')],
-                        vec![Crate('M'), Crate('C'), Crate('D')],
-                        vec![Crate('P')],
+                        vec![Crate('Z'.into()), Crate('N'.into())],
+                        vec![Crate('M'.into()), Crate('C'.into()), Crate('D'.into())],
+                        vec![Crate('P'.into())],
                     ],
                     steps: vec![
                         Step {
@@ -1,9 +1,10 @@
-use crate::parser::parse_procedure;
+use crate::{crane::Crane, parser::parse_procedure};
 use nom::{error::Error, Finish};
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
+use thiserror::Error;
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Crate(pub char);
+pub struct Crate(pub String);
 
 pub type Stack = Vec<Crate>;
 
@@ -14,12 +15,146 @@ pub struct Step {
     pub destination_stack: u32,
 }
 
+#[derive(Debug, Error, PartialEq)]
+pub enum StepError {
+    #[error("stack {0} does not exist")]
+    NoSuchStack(u32),
+    #[error("cannot move {requested} crates from stack {stack} holding {available}")]
+    NotEnoughCrates {
+        stack: u32,
+        requested: u32,
+        available: usize,
+    },
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ExecuteError {
+    #[error("invalid step {step}: {err}")]
+    InvalidStep { step: usize, err: StepError },
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Procedure {
     pub stacks: Vec<Stack>,
     pub steps: Vec<Step>,
 }
 
+impl Procedure {
+    /// Carries out every step with the given crane. Steps before an invalid
+    /// one stay applied.
+    pub fn execute(&mut self, crane: &impl Crane) -> Result<(), ExecuteError> {
+        self.replay(crane, |_, _| {})
+    }
+
+    /// Like [`Procedure::execute`], handing the stacks to `on_step` after
+    /// each step together with that step.
+    pub fn replay(
+        &mut self,
+        crane: &impl Crane,
+        mut on_step: impl FnMut(&Step, &[Stack]),
+    ) -> Result<(), ExecuteError> {
+        for (idx, step) in self.steps.iter().enumerate() {
+            Self::apply(&mut self.stacks, step, crane)
+                .map_err(|err| ExecuteError::InvalidStep { step: idx + 1, err })?;
+
+            on_step(step, &self.stacks);
+        }
+
+        Ok(())
+    }
+
+    fn apply(stacks: &mut [Stack], step: &Step, crane: &impl Crane) -> Result<(), StepError> {
+        let index = |number: u32| {
+            (number as usize)
+                .checked_sub(1)
+                .filter(|&i| i < stacks.len())
+                .ok_or(StepError::NoSuchStack(number))
+        };
+
+        let from = index(step.target_stack)?;
+        let to = index(step.destination_stack)?;
+        let available = stacks[from].len();
+
+        if step.crates_quantity as usize > available {
+            return Err(StepError::NotEnoughCrates {
+                stack: step.target_stack,
+                requested: step.crates_quantity,
+                available,
+            });
+        }
+
+        if from == to {
+            return Ok(());
+        }
+
+        // Split the stacks so both can be borrowed mutably at once.
+        let (low, high) = stacks.split_at_mut(from.max(to));
+        let (from, to) = if from < to {
+            (&mut low[from], &mut high[0])
+        } else {
+            (&mut high[0], &mut low[to])
+        };
+
+        crane.lift(step.crates_quantity as usize, from, to);
+
+        Ok(())
+    }
+
+    /// The crate on top of each stack, with a space for empty stacks.
+    pub fn top_crates(&self) -> String {
+        self.stacks
+            .iter()
+            .map(|s| s.last().map_or(" ", |c| c.0.as_str()))
+            .collect()
+    }
+}
+
+/// Draws stacks the way the puzzle does, from the top layer down to the
+/// footer of stack numbers. Columns widen to fit the longest crate or label.
+pub struct Drawing<'a>(pub &'a [Stack]);
+
+impl fmt::Display for Drawing<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let height = self.0.iter().map(Vec::len).max().unwrap_or(0);
+        let width = self
+            .0
+            .iter()
+            .flatten()
+            .map(|Crate(id)| id.chars().count())
+            .chain([self.0.len().to_string().len()])
+            .max()
+            .unwrap_or(1)
+            + 2;
+
+        for level in (0..height).rev() {
+            let cells: Vec<_> = self
+                .0
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(Crate(id)) => format!("{:^width$}", format!("[{id}]")),
+                    None => " ".repeat(width),
+                })
+                .collect();
+
+            writeln!(f, "{}", cells.join(" "))?;
+        }
+
+        let labels: Vec<_> = (1..=self.0.len()).map(|n| format!("{n:^width$}")).collect();
+
+        write!(f, "{}", labels.join(" "))
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.crates_quantity, self.target_stack, self.destination_stack
+        )
+    }
+}
+
 impl FromStr for Procedure {
     type Err = Error<String>;
 
@@ -1,13 +1,16 @@
 use color_eyre::{eyre::ContextCompat, Result};
-use day_06::part_1;
-use std::fs;
+use day_06::read_markers;
+use std::fs::File;
 
 fn main() -> Result<()> {
-    let input = fs::read_to_string("./input.txt")?;
+    let input = File::open("./input.txt")?;
 
     println!(
         "{}",
-        part_1(&input).wrap_err("expected a start-of-packet marker")?
+        read_markers(input, 4)
+            .next()
+            .transpose()?
+            .wrap_err("expected a start-of-packet marker")?
     );
 
     Ok(())
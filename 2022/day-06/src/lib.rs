@@ -1,20 +1,133 @@
-use std::collections::HashSet;
+use std::io::{self, BufReader, Read};
+
+mod signal;
+
+pub use signal::{Frame, Protocol, ProtocolError, Signal, SignalError};
 
 pub fn part_1(input: &str) -> Option<usize> {
-    marker_position(input, 4)
+    markers(input.bytes(), 4).next()
 }
 
 pub fn part_2(input: &str) -> Option<usize> {
-    marker_position(input, 14)
+    markers(input.bytes(), 14).next()
 }
 
-fn marker_position(input: &str, marker_size: usize) -> Option<usize> {
-    input
-        .chars()
-        .collect::<Vec<_>>()
-        .windows(marker_size)
-        .position(|w| HashSet::<&char>::from_iter(w).len() == marker_size)
-        .map(|p| p + marker_size)
+/// Incrementally detects markers, i.e. runs of `size` distinct bytes, in
+/// constant time and memory per byte.
+#[derive(Clone, Debug)]
+pub struct MarkerDetector {
+    window: Vec<u8>,
+    counts: [usize; 256],
+    distinct: usize,
+    position: usize,
+}
+
+impl MarkerDetector {
+    /// # Panics
+    ///
+    /// If `size` is zero.
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "a marker has at least one byte");
+
+        Self {
+            window: vec![0; size],
+            counts: [0; 256],
+            distinct: 0,
+            position: 0,
+        }
+    }
+
+    /// Feeds the next byte of the signal and tells whether it completes a
+    /// marker.
+    pub fn push(&mut self, byte: u8) -> bool {
+        let size = self.window.len();
+        let slot = self.position % size;
+
+        if self.position >= size {
+            let old = self.window[slot] as usize;
+            self.counts[old] -= 1;
+
+            if self.counts[old] == 0 {
+                self.distinct -= 1;
+            }
+        }
+
+        self.window[slot] = byte;
+        self.counts[byte as usize] += 1;
+
+        if self.counts[byte as usize] == 1 {
+            self.distinct += 1;
+        }
+
+        self.position += 1;
+
+        self.distinct == size
+    }
+
+    /// The number of bytes fed so far, which is the position reported for a
+    /// marker ending at the last byte.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+/// The positions right after every marker of `size` bytes, lazily.
+pub fn markers<I: IntoIterator<Item = u8>>(bytes: I, size: usize) -> Markers<I::IntoIter> {
+    Markers {
+        bytes: bytes.into_iter(),
+        detector: MarkerDetector::new(size),
+    }
+}
+
+pub struct Markers<I> {
+    bytes: I,
+    detector: MarkerDetector,
+}
+
+impl<I: Iterator<Item = u8>> Iterator for Markers<I> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for byte in self.bytes.by_ref() {
+            if self.detector.push(byte) {
+                return Some(self.detector.position());
+            }
+        }
+
+        None
+    }
+}
+
+/// Like [`markers`], streaming the signal from a reader through a buffer, so
+/// arbitrarily large signals are never held in memory.
+pub fn read_markers<R: Read>(reader: R, size: usize) -> ReadMarkers<R> {
+    ReadMarkers {
+        bytes: BufReader::new(reader).bytes(),
+        detector: MarkerDetector::new(size),
+    }
+}
+
+pub struct ReadMarkers<R> {
+    bytes: io::Bytes<BufReader<R>>,
+    detector: MarkerDetector,
+}
+
+impl<R: Read> Iterator for ReadMarkers<R> {
+    type Item = io::Result<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for byte in self.bytes.by_ref() {
+            match byte {
+                Ok(byte) if self.detector.push(byte) => {
+                    return Some(Ok(self.detector.position()));
+                }
+                Ok(_) => continue,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+
+        None
+    }
 }
 
 #[cfg(test)]
@@ -36,4 +149,36 @@ mod tests {
 
         assert_eq!(answer, Some(19));
     }
+
+    #[test]
+    fn test_markers() {
+        assert_eq!(
+            markers("abcabcd".bytes(), 3).collect::<Vec<_>>(),
+            vec![3, 4, 5, 6, 7]
+        );
+
+        assert_eq!(
+            markers("aabbccdd".bytes(), 2).collect::<Vec<_>>(),
+            vec![3, 5, 7]
+        );
+
+        assert_eq!(markers("aaaa".bytes(), 1).count(), 4);
+        assert_eq!(markers("abc".bytes(), 4).next(), None);
+    }
+
+    #[test]
+    fn test_read_markers() {
+        for (signal, start_of_packet, start_of_message) in [
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ] {
+            let mut packets = read_markers(signal.as_bytes(), 4);
+            let mut messages = read_markers(signal.as_bytes(), 14);
+
+            assert_eq!(packets.next().unwrap().unwrap(), start_of_packet);
+            assert_eq!(messages.next().unwrap().unwrap(), start_of_message);
+        }
+    }
 }
@@ -0,0 +1,247 @@
+use crate::MarkerDetector;
+use std::io::{self, BufReader, Bytes, Read};
+use thiserror::Error;
+
+/// How transmissions are framed in a datastream.
+///
+/// Transmissions are separated by `delimiter`, which is assumed never to
+/// occur inside one: the markers cannot delimit transmissions on their own,
+/// since any payload is likely to contain a run of distinct bytes. A stream
+/// without a delimiter is therefore a single transmission.
+///
+/// Each transmission must contain a start-of-packet marker and a
+/// start-of-message marker, both searched from the start of the
+/// transmission, and everything after the start-of-message marker is the
+/// payload.
+#[derive(Clone, Copy, Debug)]
+pub struct Protocol {
+    pub packet_marker: usize,
+    pub message_marker: usize,
+    pub delimiter: u8,
+}
+
+impl Default for Protocol {
+    fn default() -> Self {
+        Self {
+            packet_marker: 4,
+            message_marker: 14,
+            delimiter: b'\n',
+        }
+    }
+}
+
+impl Protocol {
+    pub fn decode<R: Read>(self, reader: R) -> Result<Signal<R>, ProtocolError> {
+        for size in [self.packet_marker, self.message_marker] {
+            if !(1..=256).contains(&size) {
+                return Err(ProtocolError::InvalidMarkerSize(size));
+            }
+        }
+
+        Ok(Signal {
+            protocol: self,
+            bytes: BufReader::new(reader).bytes(),
+            position: 0,
+        })
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ProtocolError {
+    #[error("a marker has between 1 and 256 distinct bytes, found {0}")]
+    InvalidMarkerSize(usize),
+}
+
+/// A decoded transmission. Positions count bytes from the start of the
+/// datastream and point right after the corresponding marker.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub start_of_packet: usize,
+    pub start_of_message: usize,
+    pub payload: Vec<u8>,
+}
+
+#[derive(Debug, Error)]
+pub enum SignalError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("no start-of-packet marker in the transmission at {start}")]
+    NoPacketMarker { start: usize },
+    #[error("no start-of-message marker in the transmission at {start}")]
+    NoMessageMarker {
+        start: usize,
+        start_of_packet: usize,
+    },
+}
+
+/// The frames of a datastream, decoded lazily. A malformed transmission is
+/// reported as an error and decoding carries on with the next one.
+pub struct Signal<R> {
+    protocol: Protocol,
+    bytes: Bytes<BufReader<R>>,
+    position: usize,
+}
+
+impl<R: Read> Iterator for Signal<R> {
+    type Item = Result<Frame, SignalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.position;
+            let mut packet = MarkerDetector::new(self.protocol.packet_marker);
+            let mut message = MarkerDetector::new(self.protocol.message_marker);
+            let mut start_of_packet = None;
+            let mut start_of_message = None;
+            let mut payload = vec![];
+            let mut delimited = false;
+
+            for byte in self.bytes.by_ref() {
+                let byte = match byte {
+                    Ok(byte) => byte,
+                    Err(err) => return Some(Err(err.into())),
+                };
+
+                self.position += 1;
+
+                if byte == self.protocol.delimiter {
+                    delimited = true;
+                    break;
+                }
+
+                if start_of_message.is_some() {
+                    payload.push(byte);
+                    continue;
+                }
+
+                if packet.push(byte) && start_of_packet.is_none() {
+                    start_of_packet = Some(start + packet.position());
+                }
+
+                if message.push(byte) {
+                    start_of_message = Some(start + message.position());
+                }
+            }
+
+            // Skip blank transmissions, such as after a trailing delimiter.
+            if self.position - start == delimited as usize {
+                if delimited {
+                    continue;
+                }
+
+                return None;
+            }
+
+            return Some(match (start_of_packet, start_of_message) {
+                (Some(start_of_packet), Some(start_of_message)) => Ok(Frame {
+                    start_of_packet,
+                    start_of_message,
+                    payload,
+                }),
+                (None, _) => Err(SignalError::NoPacketMarker { start }),
+                (Some(start_of_packet), None) => Err(SignalError::NoMessageMarker {
+                    start,
+                    start_of_packet,
+                }),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let datastream = "\
+mjqjpqmgbljsphdztnvjfqwrcgsmlb
+aaaa
+
+abcdaaaaaaaaaaaaaa
+abcdefghijklmnop
+";
+
+        let frames = Protocol::default()
+            .decode(datastream.as_bytes())
+            .unwrap()
+            .map(|frame| frame.map_err(|err| err.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            frames,
+            vec![
+                Ok(Frame {
+                    start_of_packet: 7,
+                    start_of_message: 19,
+                    payload: b"jfqwrcgsmlb".to_vec()
+                }),
+                Err("no start-of-packet marker in the transmission at 31".to_owned()),
+                Err("no start-of-message marker in the transmission at 37".to_owned()),
+                Ok(Frame {
+                    start_of_packet: 60,
+                    start_of_message: 70,
+                    payload: b"op".to_vec()
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_protocol() {
+        let protocol = Protocol {
+            packet_marker: 2,
+            message_marker: 3,
+            delimiter: b'|',
+        };
+
+        let frames = protocol
+            .decode(&b"aabcdd|xyz"[..])
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            frames,
+            vec![
+                Frame {
+                    start_of_packet: 3,
+                    start_of_message: 4,
+                    payload: b"dd".to_vec()
+                },
+                Frame {
+                    start_of_packet: 9,
+                    start_of_message: 10,
+                    payload: vec![]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_marker_size() {
+        for (packet_marker, message_marker, size) in [(0, 14, 0), (4, 0, 0), (4, 257, 257)] {
+            let protocol = Protocol {
+                packet_marker,
+                message_marker,
+                ..Protocol::default()
+            };
+
+            assert_eq!(
+                protocol.decode(&b"abcd"[..]).err(),
+                Some(ProtocolError::InvalidMarkerSize(size))
+            );
+        }
+    }
+
+    #[test]
+    fn test_undelimited_stream() {
+        let frames = Protocol::default()
+            .decode(&b"mjqjpqmgbljsphdztnvjfqwrcgsmlb"[..])
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].payload, b"jfqwrcgsmlb");
+    }
+}
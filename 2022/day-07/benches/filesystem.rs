@@ -0,0 +1,47 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day_07::{part_1, Synthetic};
+
+fn deep_output(depth: usize) -> String {
+    "$ ls\ndir d\n1 f\n$ cd d\n".repeat(depth)
+}
+
+fn bench_deep_tree(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deep_tree");
+
+    for depth in [1_000, 2_000, 4_000, 8_000, 16_000] {
+        let output = deep_output(depth);
+
+        group.throughput(Throughput::Elements(depth as u64));
+        group.bench_with_input(BenchmarkId::new("part_1", depth), &output, |b, output| {
+            b.iter(|| part_1(output.parse().unwrap()))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_synthetic(c: &mut Criterion) {
+    let mut group = c.benchmark_group("synthetic");
+
+    for dirs in [1_000, 10_000] {
+        let output = Synthetic {
+            dirs,
+            files: dirs * 4,
+            max_file_size: 300_000,
+            seed: 7,
+        }
+        .generate()
+        .transcript()
+        .to_string();
+
+        group.throughput(Throughput::Bytes(output.len() as u64));
+        group.bench_with_input(BenchmarkId::new("part_1", dirs), &output, |b, output| {
+            b.iter(|| part_1(output.parse().unwrap()))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_deep_tree, bench_synthetic);
+criterion_main!(benches);
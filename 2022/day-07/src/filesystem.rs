@@ -0,0 +1,622 @@
+use crate::{Command, DirPath, Output, OutputLine, PathSegment};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    ops::Index,
+};
+use thiserror::Error;
+
+/// Index of an [`Item`] inside the [`Filesystem`] arena.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ItemId(usize);
+
+impl ItemId {
+    pub const ROOT: Self = Self(0);
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dir {
+    name: String,
+    items: Vec<ItemId>,
+    size: u64,
+}
+
+impl Dir {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            name,
+            items: vec![],
+            size: 0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn items(&self) -> &[ItemId] {
+        &self.items
+    }
+
+    /// The cumulative size of everything below this directory.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Item {
+    Dir(Dir),
+    File { name: String, size: u64 },
+}
+
+impl Item {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Dir(dir) => dir.name(),
+            Self::File { name, .. } => name,
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        match self {
+            Self::Dir(dir) => dir.size(),
+            Self::File { size, .. } => *size,
+        }
+    }
+}
+
+/// A directory tree rebuilt from the terminal output, rooted at `/`.
+///
+/// Items live in a flat arena and refer to each other by [`ItemId`], with
+/// children always stored after their parent. This lets directory sizes be
+/// accumulated in a single reverse pass once the tree is complete.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Filesystem {
+    items: Vec<Item>,
+    parents: Vec<Option<ItemId>>,
+}
+
+impl Filesystem {
+    pub fn root(&self) -> &Dir {
+        self.dir(ItemId::ROOT)
+            .expect("the root is always a directory")
+    }
+
+    pub fn dir(&self, id: ItemId) -> Option<&Dir> {
+        match &self[id] {
+            Item::Dir(dir) => Some(dir),
+            Item::File { .. } => None,
+        }
+    }
+
+    pub fn dirs(&self) -> impl Iterator<Item = &Dir> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Dir(dir) => Some(dir),
+            Item::File { .. } => None,
+        })
+    }
+
+    pub fn dir_ids(&self) -> impl Iterator<Item = ItemId> + '_ {
+        (0..self.items.len())
+            .map(ItemId)
+            .filter(|&id| self.dir(id).is_some())
+    }
+
+    fn child(&self, dir: &Dir, name: &str) -> Option<ItemId> {
+        dir.items
+            .iter()
+            .copied()
+            .find(|&id| self[id].name() == name)
+    }
+
+    /// Looks up the item at a `/` separated path, e.g. `/a/e/i`. The leading
+    /// slash is optional and `/` alone refers to the root directory.
+    pub fn find(&self, path: &str) -> Option<&Item> {
+        path.split('/')
+            .filter(|segment| !segment.is_empty())
+            .try_fold(ItemId::ROOT, |id, segment| {
+                self.child(self.dir(id)?, segment)
+            })
+            .map(|id| &self[id])
+    }
+
+    /// The absolute path of an item, e.g. `/a/e/i`.
+    pub fn path(&self, id: ItemId) -> String {
+        let mut names = vec![];
+        let mut current = id;
+
+        while let Some(parent) = self.parent(current) {
+            names.push(self[current].name());
+            current = parent;
+        }
+
+        names.reverse();
+
+        format!("/{}", names.join("/"))
+    }
+
+    /// The directory containing an item, or `None` for the root.
+    pub fn parent(&self, ItemId(index): ItemId) -> Option<ItemId> {
+        self.parents[index]
+    }
+
+    /// Iterates depth-first over every directory and file, in listing order,
+    /// yielding its absolute path and total size.
+    pub fn walk(&self) -> Walk<'_> {
+        Walk {
+            filesystem: self,
+            stack: vec![("/".to_owned(), ItemId::ROOT)],
+        }
+    }
+
+    /// Replays the tree as a canonical terminal session: every directory is
+    /// entered once from its parent, listed, and left again with `cd ..`.
+    pub fn transcript(&self) -> Output {
+        enum Step {
+            Enter(ItemId),
+            Leave,
+        }
+
+        let cd =
+            |absolute, segments| OutputLine::Command(Command::Cd(DirPath { absolute, segments }));
+
+        let mut lines = vec![cd(true, vec![])];
+        let mut steps = vec![Step::Enter(ItemId::ROOT)];
+
+        while let Some(step) = steps.pop() {
+            let id = match step {
+                Step::Enter(id) => id,
+                Step::Leave => {
+                    lines.push(cd(false, vec![PathSegment::Previous]));
+                    continue;
+                }
+            };
+
+            if id != ItemId::ROOT {
+                let name = self[id].name().to_owned();
+                lines.push(cd(false, vec![PathSegment::Name(name)]));
+            }
+
+            lines.push(OutputLine::Command(Command::Ls));
+
+            let dir = self.dir(id).expect("only directories are entered");
+
+            for &id in &dir.items {
+                lines.push(match &self[id] {
+                    Item::Dir(dir) => OutputLine::Dir(dir.name.clone()),
+                    Item::File { name, size } => OutputLine::File(*size, name.clone()),
+                });
+            }
+
+            for &id in dir.items.iter().rev() {
+                if self.dir(id).is_some() {
+                    steps.push(Step::Leave);
+                    steps.push(Step::Enter(id));
+                }
+            }
+        }
+
+        Output(lines)
+    }
+
+    pub(crate) fn empty() -> Self {
+        Self {
+            items: vec![Item::Dir(Dir::new("/".to_owned()))],
+            parents: vec![None],
+        }
+    }
+
+    pub(crate) fn push(&mut self, parent: ItemId, item: Item) -> ItemId {
+        let id = ItemId(self.items.len());
+        self.items.push(item);
+        self.parents.push(Some(parent));
+
+        if let Item::Dir(dir) = &mut self.items[parent.0] {
+            dir.items.push(id);
+        }
+
+        id
+    }
+
+    pub(crate) fn compute_sizes(&mut self) -> Result<(), FilesystemError> {
+        for index in (0..self.items.len()).rev() {
+            let Item::Dir(dir) = &self.items[index] else {
+                continue;
+            };
+
+            let size = dir
+                .items
+                .iter()
+                .try_fold(0u64, |total, &id| total.checked_add(self[id].size()))
+                .ok_or_else(|| FilesystemError::SizeOverflow {
+                    path: self.path(ItemId(index)),
+                })?;
+
+            if let Item::Dir(dir) = &mut self.items[index] {
+                dir.size = size;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Index<ItemId> for Filesystem {
+    type Output = Item;
+
+    fn index(&self, ItemId(index): ItemId) -> &Self::Output {
+        &self.items[index]
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum FilesystemError {
+    #[error("line {line}: no such directory {name:?} in {path}")]
+    UnknownDir {
+        line: usize,
+        path: String,
+        name: String,
+    },
+    #[error("line {line}: {name:?} in {path} is not a directory")]
+    NotADir {
+        line: usize,
+        path: String,
+        name: String,
+    },
+    #[error("line {line}: {path} was already listed")]
+    DuplicateListing { line: usize, path: String },
+    #[error("line {line}: {name:?} is listed twice in {path}")]
+    DuplicateEntry {
+        line: usize,
+        path: String,
+        name: String,
+    },
+    #[error("the total size of {path} does not fit in a u64")]
+    SizeOverflow { path: String },
+}
+
+struct Builder {
+    filesystem: Filesystem,
+    children: HashMap<(ItemId, String), ItemId>,
+    listed: HashSet<ItemId>,
+    cwd: ItemId,
+}
+
+impl Builder {
+    fn cd(
+        &mut self,
+        line: usize,
+        DirPath { absolute, segments }: DirPath,
+    ) -> Result<(), FilesystemError> {
+        if absolute {
+            self.cwd = ItemId::ROOT;
+        }
+
+        for segment in segments {
+            match segment {
+                PathSegment::Current => {}
+                PathSegment::Previous => {
+                    self.cwd = self.filesystem.parent(self.cwd).unwrap_or(self.cwd);
+                }
+                PathSegment::Name(name) => {
+                    let key = (self.cwd, name);
+
+                    let Some(&id) = self.children.get(&key) else {
+                        return Err(FilesystemError::UnknownDir {
+                            line,
+                            path: self.filesystem.path(self.cwd),
+                            name: key.1,
+                        });
+                    };
+
+                    if self.filesystem.dir(id).is_none() {
+                        return Err(FilesystemError::NotADir {
+                            line,
+                            path: self.filesystem.path(self.cwd),
+                            name: key.1,
+                        });
+                    }
+
+                    self.cwd = id;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn ls(&mut self, line: usize) -> Result<(), FilesystemError> {
+        if !self.listed.insert(self.cwd) {
+            return Err(FilesystemError::DuplicateListing {
+                line,
+                path: self.filesystem.path(self.cwd),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn add(&mut self, line: usize, item: Item) -> Result<(), FilesystemError> {
+        let key = (self.cwd, item.name().to_owned());
+
+        if self.children.contains_key(&key) {
+            return Err(FilesystemError::DuplicateEntry {
+                line,
+                path: self.filesystem.path(self.cwd),
+                name: key.1,
+            });
+        }
+
+        let id = self.filesystem.push(self.cwd, item);
+        self.children.insert(key, id);
+
+        Ok(())
+    }
+}
+
+impl TryFrom<Output> for Filesystem {
+    type Error = FilesystemError;
+
+    fn try_from(output: Output) -> Result<Self, Self::Error> {
+        let mut builder = Builder {
+            filesystem: Self::empty(),
+            children: HashMap::new(),
+            listed: HashSet::new(),
+            cwd: ItemId::ROOT,
+        };
+
+        for (idx, output_line) in output.0.into_iter().enumerate() {
+            let line = idx + 1;
+
+            match output_line {
+                OutputLine::Command(Command::Cd(path)) => builder.cd(line, path)?,
+                OutputLine::Command(Command::Ls) => builder.ls(line)?,
+                OutputLine::Dir(name) => builder.add(line, Item::Dir(Dir::new(name)))?,
+                OutputLine::File(size, name) => builder.add(line, Item::File { name, size })?,
+            }
+        }
+
+        let mut filesystem = builder.filesystem;
+        filesystem.compute_sizes()?;
+
+        Ok(filesystem)
+    }
+}
+
+impl fmt::Display for Filesystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut stack = vec![(0, ItemId::ROOT)];
+
+        while let Some((depth, id)) = stack.pop() {
+            let indent = "  ".repeat(depth);
+
+            match &self[id] {
+                Item::Dir(dir) => {
+                    writeln!(f, "{indent}- {} (dir)", dir.name)?;
+                    stack.extend(dir.items.iter().rev().map(|&id| (depth + 1, id)));
+                }
+                Item::File { name, size } => {
+                    writeln!(f, "{indent}- {name} (file, size={size})")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Walk<'a> {
+    filesystem: &'a Filesystem,
+    stack: Vec<(String, ItemId)>,
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = (String, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, id) = self.stack.pop()?;
+        let item = &self.filesystem[id];
+
+        if let Item::Dir(dir) = item {
+            let prefix = path.trim_end_matches('/');
+
+            self.stack.extend(
+                dir.items
+                    .iter()
+                    .rev()
+                    .map(|&id| (format!("{prefix}/{}", self.filesystem[id].name()), id)),
+            );
+        }
+
+        Some((path, item.size()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::OUTPUT, Synthetic};
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_find() {
+        let filesystem = OUTPUT.parse::<Filesystem>().unwrap();
+
+        assert_eq!(filesystem.find("/").map(Item::size), Some(48381165));
+        assert_eq!(filesystem.find("/a").map(Item::size), Some(94853));
+        assert_eq!(filesystem.find("/a/e/i").map(Item::size), Some(584));
+        assert_eq!(filesystem.find("d/d.log").map(Item::size), Some(8033020));
+        assert_eq!(filesystem.find("/a/x"), None);
+        assert_eq!(filesystem.find("/b.txt/x"), None);
+    }
+
+    #[test]
+    fn test_errors() {
+        for (output, err) in [
+            (
+                "$ cd /\n$ ls\ndir a\n$ cd b",
+                FilesystemError::UnknownDir {
+                    line: 4,
+                    path: "/".to_owned(),
+                    name: "b".to_owned(),
+                },
+            ),
+            (
+                "$ ls\ndir a\n1 f\n$ cd a/../f",
+                FilesystemError::NotADir {
+                    line: 4,
+                    path: "/".to_owned(),
+                    name: "f".to_owned(),
+                },
+            ),
+            (
+                "$ ls\ndir a\n$ cd a\n$ ls\n$ cd /a\n$ ls",
+                FilesystemError::DuplicateListing {
+                    line: 6,
+                    path: "/a".to_owned(),
+                },
+            ),
+            (
+                "$ ls\ndir a\n$ cd a\n$ ls\n1 x\n2 x",
+                FilesystemError::DuplicateEntry {
+                    line: 6,
+                    path: "/a".to_owned(),
+                    name: "x".to_owned(),
+                },
+            ),
+            (
+                "$ ls\n18446744073709551615 a\n1 b\n",
+                FilesystemError::SizeOverflow {
+                    path: "/".to_owned(),
+                },
+            ),
+            (
+                "$ ls\ndir a\n$ cd a\n$ ls\n9223372036854775808 x\n9223372036854775808 y\n",
+                FilesystemError::SizeOverflow {
+                    path: "/a".to_owned(),
+                },
+            ),
+        ] {
+            let output = output.parse::<Output>().unwrap();
+
+            assert_eq!(Filesystem::try_from(output), Err(err));
+        }
+    }
+
+    #[test]
+    fn test_multi_segment_cd() {
+        let filesystem = "\
+$ ls
+dir a-1
+$ cd a-1
+$ ls
+dir b_2
+$ cd b_2
+$ ls
+8589934592 c d.bin
+dir e
+$ cd /
+$ cd a-1/./b_2/../b_2/e/
+$ ls
+1 f
+$ cd /a-1/b_2
+"
+        .parse::<Filesystem>()
+        .unwrap();
+
+        assert_eq!(filesystem.root().size(), 8589934593);
+        assert_eq!(
+            filesystem.find("/a-1/b_2/c d.bin").map(Item::size),
+            Some(8589934592)
+        );
+        assert_eq!(filesystem.find("/a-1/b_2/e/f").map(Item::size), Some(1));
+    }
+
+    #[test]
+    fn test_walk() {
+        let filesystem = OUTPUT.parse::<Filesystem>().unwrap();
+
+        assert_eq!(
+            filesystem.walk().collect::<Vec<_>>(),
+            vec![
+                ("/".to_owned(), 48381165),
+                ("/a".to_owned(), 94853),
+                ("/a/e".to_owned(), 584),
+                ("/a/e/i".to_owned(), 584),
+                ("/a/f".to_owned(), 29116),
+                ("/a/g".to_owned(), 2557),
+                ("/a/h.lst".to_owned(), 62596),
+                ("/b.txt".to_owned(), 14848514),
+                ("/c.dat".to_owned(), 8504156),
+                ("/d".to_owned(), 24933642),
+                ("/d/j".to_owned(), 4060174),
+                ("/d/d.log".to_owned(), 8033020),
+                ("/d/d.ext".to_owned(), 5626152),
+                ("/d/k".to_owned(), 7214296),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let filesystem = OUTPUT.parse::<Filesystem>().unwrap();
+
+        assert_eq!(
+            filesystem.to_string(),
+            "\
+- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - j (file, size=4060174)
+    - d.log (file, size=8033020)
+    - d.ext (file, size=5626152)
+    - k (file, size=7214296)
+"
+        );
+    }
+
+    #[test]
+    fn test_transcript() {
+        let filesystem = OUTPUT.parse::<Filesystem>().unwrap();
+
+        assert_eq!(
+            filesystem.transcript().to_string(),
+            format!("{OUTPUT}$ cd ..\n")
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_transcript_round_trip(
+            dirs in 0..50usize,
+            files in 0..100usize,
+            max_file_size in 1..1_000_000u64,
+            seed: u64,
+        ) {
+            let filesystem = Synthetic { dirs, files, max_file_size, seed }.generate();
+            let transcript = filesystem.transcript();
+            let replayed = transcript.to_string().parse::<Filesystem>().unwrap();
+
+            prop_assert_eq!(replayed.to_string(), filesystem.to_string());
+            prop_assert_eq!(replayed.transcript(), transcript);
+        }
+    }
+
+    #[test]
+    fn test_deep_tree() {
+        let depth = 10_000;
+        let output = "$ ls\ndir d\n1 f\n$ cd d\n".repeat(depth);
+        let filesystem = output.parse::<Filesystem>().unwrap();
+
+        assert_eq!(filesystem.root().size(), depth as u64);
+        assert_eq!(filesystem.dirs().count(), depth + 1);
+    }
+}
@@ -1,201 +1,116 @@
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::{alphanumeric1, char, newline, space1, u32},
-    combinator::{map, recognize, value},
+    bytes::complete::{tag, take_while1},
+    character::complete::{char, newline, space1, u64},
+    combinator::{all_consuming, map, opt, value, verify},
     error::Error,
-    multi::{many1, separated_list0},
-    sequence::{preceded, separated_pair},
+    multi::separated_list0,
+    sequence::{pair, preceded, separated_pair, terminated},
     Finish, IResult,
 };
-use std::str::FromStr;
-
-fn make_filesystem(output: Output) -> Dir {
-    let mut zipper = Zipper(
-        Dir {
-            name: "/".to_owned(),
-            items: vec![],
-        },
-        vec![],
-    );
-
-    for line in output.0 {
-        match line {
-            OutputLine::Command(cmd) => match cmd {
-                Command::Cd(path) => match path {
-                    DirPath::Root => {
-                        zipper = zipper.cd_root();
-                    }
-                    DirPath::Previous => {
-                        zipper = zipper.cd_previous();
-                    }
-                    DirPath::Name(name) => {
-                        zipper = zipper.cd(name);
-                    }
-                },
-                Command::Ls => {
-                    continue;
-                }
-            },
-            OutputLine::Dir(name) => {
-                zipper.0.items.push(Item::Dir(Dir {
-                    name,
-                    items: vec![],
-                }));
-            }
-            OutputLine::File(size, _name) => {
-                zipper.0.items.push(Item::File { size });
-            }
-        }
-    }
+use std::{fmt, str::FromStr};
+use thiserror::Error;
 
-    zipper.cd_root().0
-}
+mod filesystem;
+mod planner;
+mod synthetic;
 
-pub fn part_1(output: Output) -> u32 {
-    let root = make_filesystem(output);
-    let mut dirs = root.subdirs();
-    dirs.push(&root);
+pub use filesystem::{Dir, Filesystem, FilesystemError, Item, ItemId, Walk};
+pub use planner::{Deletion, PlanError, Planner};
+pub use synthetic::Synthetic;
 
-    dirs.into_iter()
+pub fn part_1(filesystem: Filesystem) -> u64 {
+    filesystem
+        .dirs()
         .map(Dir::size)
         .filter(|s| *s <= 100_000)
         .sum()
 }
 
-pub fn part_2(output: Output) -> u32 {
-    let root = make_filesystem(output);
-    let mut dirs = root.subdirs();
-    dirs.push(&root);
-
-    let total_space = 70_000_000;
-    let used_space = root.size();
-    let unused_space = total_space - used_space;
-    let required_space = 30_000_000;
-    let space_to_erase = if unused_space < required_space {
-        required_space - unused_space
-    } else {
-        0
-    };
-
-    dirs.into_iter()
-        .map(Dir::size)
-        .filter(|s| *s > space_to_erase)
-        .min()
-        .unwrap()
-}
-
-#[derive(Debug)]
-struct Crumb(String, Vec<Item>, Vec<Item>);
-
-#[derive(Debug)]
-struct Zipper(Dir, Vec<Crumb>);
-
-impl Zipper {
-    fn cd_previous(mut self) -> Self {
-        let Some(Crumb(name, prev, next)) = self.1.pop() else {
-            return self;
-        };
-
-        let mut items = prev;
-        items.push(Item::Dir(self.0));
-        items.extend_from_slice(&next);
-
-        Zipper(Dir { name, items }, self.1)
-    }
-
-    fn cd_root(self) -> Self {
-        let mut zipper = self;
-
-        while !zipper.1.is_empty() {
-            zipper = zipper.cd_previous();
-        }
-
-        zipper
-    }
-
-    fn cd(self, name: String) -> Self {
-        let Self(current_dir, crumbs) = self;
-
-        for (index, item) in current_dir.items.iter().enumerate() {
-            let Item::Dir(dir) = item else {
-                continue;
-            };
-
-            if dir.name != name {
-                continue;
-            }
-
-            let mut crumbs = crumbs;
+pub fn part_2(filesystem: Filesystem) -> Result<u64, PlanError> {
+    let deletion = Planner::default().smallest_dir(&filesystem)?;
 
-            crumbs.push(Crumb(
-                current_dir.name,
-                current_dir.items[0..index].to_owned(),
-                current_dir.items[index + 1..].to_owned(),
-            ));
-
-            return Zipper(dir.to_owned(), crumbs);
-        }
+    Ok(deletion.map_or(0, |d| d.size))
+}
 
-        Self(current_dir, crumbs)
-    }
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathSegment {
+    Current,
+    Previous,
+    Name(String),
 }
 
-#[derive(Clone, Debug)]
-struct Dir {
-    name: String,
-    items: Vec<Item>,
+/// The argument of `cd`, e.g. `/`, `..`, `a` or `/a/b/../c`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DirPath {
+    pub absolute: bool,
+    pub segments: Vec<PathSegment>,
 }
 
-impl Dir {
-    fn size(&self) -> u32 {
-        self.items.iter().map(Item::size).sum()
-    }
+fn parse_name(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c != '/' && !c.is_control())(input)
+}
 
-    fn subdirs(&self) -> Vec<&Dir> {
-        self.items
-            .iter()
-            .filter_map(|i| match i {
-                Item::Dir(d) => Some(d),
-                _ => None,
-            })
-            .flat_map(|d| {
-                let mut subdirs = d.subdirs();
-                subdirs.push(d);
-                subdirs
-            })
-            .collect()
-    }
+/// The name of a listed dir or file. `.` and `..` are rejected, as `cd` reads
+/// them as the current and parent dirs and could never enter such an entry.
+fn parse_entry_name(input: &str) -> IResult<&str, &str> {
+    verify(parse_name, |name: &str| name != "." && name != "..")(input)
 }
 
-#[derive(Clone, Debug)]
-enum Item {
-    Dir(Dir),
-    File { size: u32 },
+fn parse_path_segment(input: &str) -> IResult<&str, PathSegment> {
+    map(parse_name, |name| match name {
+        "." => PathSegment::Current,
+        ".." => PathSegment::Previous,
+        _ => PathSegment::Name(name.to_owned()),
+    })(input)
 }
 
-impl Item {
-    fn size(&self) -> u32 {
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Dir(dir) => dir.size(),
-            Self::File { size } => *size,
+            Self::Current => write!(f, "."),
+            Self::Previous => write!(f, ".."),
+            Self::Name(name) => write!(f, "{name}"),
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
-pub enum DirPath {
-    Name(String),
-    Previous,
-    Root,
+/// A path with at least the root or one segment, so a bare `cd ` is an error.
+fn parse_dir_path(input: &str) -> IResult<&str, DirPath> {
+    verify(
+        map(
+            terminated(
+                pair(
+                    opt(char('/')),
+                    separated_list0(char('/'), parse_path_segment),
+                ),
+                opt(char('/')),
+            ),
+            |(root, segments)| DirPath {
+                absolute: root.is_some(),
+                segments,
+            },
+        ),
+        |path: &DirPath| path.absolute || !path.segments.is_empty(),
+    )(input)
 }
 
-fn parse_dir_path(input: &str) -> IResult<&str, DirPath> {
-    alt((
-        value(DirPath::Root, char('/')),
-        value(DirPath::Previous, tag("..")),
-        map(alphanumeric1, |s: &str| DirPath::Name(s.to_owned())),
-    ))(input)
+impl fmt::Display for DirPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.absolute {
+            write!(f, "/")?;
+        }
+
+        for (idx, segment) in self.segments.iter().enumerate() {
+            if idx > 0 {
+                write!(f, "/")?;
+            }
+
+            write!(f, "{segment}")?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -211,35 +126,66 @@ fn parse_command(input: &str) -> IResult<&str, Command> {
     ))(input)
 }
 
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cd(path) => write!(f, "cd {path}"),
+            Self::Ls => write!(f, "ls"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum OutputLine {
     Command(Command),
     Dir(String),
-    File(u32, String),
+    File(u64, String),
 }
 
 fn parse_output_line(input: &str) -> IResult<&str, OutputLine> {
     alt((
         map(preceded(tag("$ "), parse_command), OutputLine::Command),
-        map(preceded(tag("dir "), alphanumeric1), |name: &str| {
+        map(preceded(tag("dir "), parse_entry_name), |name: &str| {
             OutputLine::Dir(name.to_owned())
         }),
         map(
-            separated_pair(
-                u32,
-                space1,
-                recognize(many1(alt((alphanumeric1, tag("."))))),
-            ),
+            separated_pair(u64, space1, parse_entry_name),
             |(size, name): (_, &str)| OutputLine::File(size, name.to_owned()),
         ),
     ))(input)
 }
 
+impl fmt::Display for OutputLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Command(cmd) => write!(f, "$ {cmd}"),
+            Self::Dir(name) => write!(f, "dir {name}"),
+            Self::File(size, name) => write!(f, "{size} {name}"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Output(Vec<OutputLine>);
 
 fn parse_output(input: &str) -> IResult<&str, Output> {
-    map(separated_list0(newline, parse_output_line), Output)(input)
+    map(
+        all_consuming(terminated(
+            separated_list0(newline, parse_output_line),
+            opt(newline),
+        )),
+        Output,
+    )(input)
+}
+
+impl fmt::Display for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.0 {
+            writeln!(f, "{line}")?;
+        }
+
+        Ok(())
+    }
 }
 
 impl FromStr for Output {
@@ -256,11 +202,29 @@ impl FromStr for Output {
     }
 }
 
+#[derive(Debug, Error)]
+pub enum InputError {
+    #[error("invalid terminal output")]
+    OutputError(#[source] Error<String>),
+    #[error(transparent)]
+    FilesystemError(#[from] FilesystemError),
+}
+
+impl FromStr for Filesystem {
+    type Err = InputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let output = s.parse::<Output>().map_err(InputError::OutputError)?;
+
+        Ok(output.try_into()?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const OUTPUT: &str = "\
+    pub(crate) const OUTPUT: &str = "\
 $ cd /
 $ ls
 dir a
@@ -290,17 +254,57 @@ $ ls
     fn test_parse_command() {
         assert_eq!(
             parse_command("cd x"),
-            Ok(("", Command::Cd(DirPath::Name("x".to_owned()))))
+            Ok((
+                "",
+                Command::Cd(DirPath {
+                    absolute: false,
+                    segments: vec![PathSegment::Name("x".to_owned())]
+                })
+            ))
         );
 
         assert_eq!(
             parse_command("cd .."),
-            Ok(("", Command::Cd(DirPath::Previous)))
+            Ok((
+                "",
+                Command::Cd(DirPath {
+                    absolute: false,
+                    segments: vec![PathSegment::Previous]
+                })
+            ))
+        );
+
+        assert_eq!(
+            parse_command("cd /"),
+            Ok((
+                "",
+                Command::Cd(DirPath {
+                    absolute: true,
+                    segments: vec![]
+                })
+            ))
         );
 
-        assert_eq!(parse_command("cd /"), Ok(("", Command::Cd(DirPath::Root))));
+        assert_eq!(
+            parse_command("cd /a-1/../b_2/./c d/"),
+            Ok((
+                "",
+                Command::Cd(DirPath {
+                    absolute: true,
+                    segments: vec![
+                        PathSegment::Name("a-1".to_owned()),
+                        PathSegment::Previous,
+                        PathSegment::Name("b_2".to_owned()),
+                        PathSegment::Current,
+                        PathSegment::Name("c d".to_owned()),
+                    ]
+                })
+            ))
+        );
 
         assert_eq!(parse_command("ls"), Ok(("", Command::Ls)));
+        assert!(parse_command("cd ").is_err());
+        assert!("$ cd /\n$ cd \n$ ls\n".parse::<Output>().is_err());
     }
 
     #[test]
@@ -317,17 +321,54 @@ $ cd a
 "
             .parse(),
             Ok(Output(vec![
-                OutputLine::Command(Command::Cd(DirPath::Root)),
+                OutputLine::Command(Command::Cd(DirPath {
+                    absolute: true,
+                    segments: vec![]
+                })),
                 OutputLine::Command(Command::Ls),
                 OutputLine::Dir("a".to_owned()),
                 OutputLine::File(14848514, "b.txt".to_owned()),
                 OutputLine::File(8504156, "c.dat".to_owned()),
                 OutputLine::Dir("d".to_owned()),
-                OutputLine::Command(Command::Cd(DirPath::Name("a".to_owned()))),
+                OutputLine::Command(Command::Cd(DirPath {
+                    absolute: false,
+                    segments: vec![PathSegment::Name("a".to_owned())]
+                })),
             ]))
         )
     }
 
+    #[test]
+    fn test_parse_output_line() {
+        assert_eq!(
+            parse_output_line("dir my-dir_2"),
+            Ok(("", OutputLine::Dir("my-dir_2".to_owned())))
+        );
+
+        assert_eq!(
+            parse_output_line("8589934592 big file~(1).tar.gz"),
+            Ok((
+                "",
+                OutputLine::File(8589934592, "big file~(1).tar.gz".to_owned())
+            ))
+        );
+
+        assert!(parse_output_line("dir a/b").is_ok_and(|(rest, _)| rest == "/b"));
+        assert!("$ cd /\n$ rm -rf /\n".parse::<Output>().is_err());
+
+        for line in ["dir .", "dir ..", "10 .", "10 .."] {
+            assert!(
+                format!("$ ls\n{line}\n").parse::<Output>().is_err(),
+                "{line}"
+            );
+        }
+
+        assert_eq!(
+            parse_output_line("dir ...").map(|(_, line)| line),
+            Ok(OutputLine::Dir("...".to_owned()))
+        );
+    }
+
     #[test]
     fn test_part_1() {
         let answer = part_1(OUTPUT.parse().unwrap());
@@ -339,6 +380,6 @@ $ cd a
     fn test_part_2() {
         let answer = part_2(OUTPUT.parse().unwrap());
 
-        assert_eq!(answer, 24933642);
+        assert_eq!(answer, Ok(24933642));
     }
 }
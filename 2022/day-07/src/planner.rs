@@ -0,0 +1,475 @@
+use crate::{Filesystem, ItemId};
+use thiserror::Error;
+
+/// A directory chosen for deletion.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deletion {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum PlanError {
+    #[error("used space {used} exceeds the total space {total}")]
+    Overfull { used: u64, total: u64 },
+    #[error("required space {required} exceeds the total space {total}")]
+    NotEnoughSpace { required: u64, total: u64 },
+    #[error("cannot free {to_free}, the candidate directories only hold {freeable}")]
+    Unreachable { to_free: u64, freeable: u64 },
+}
+
+/// Chooses directories to delete so that `required_space` is left unused on
+/// a disk of `total_space`.
+#[derive(Clone, Copy, Debug)]
+pub struct Planner {
+    total_space: u64,
+    required_space: u64,
+    include_root: bool,
+}
+
+impl Default for Planner {
+    fn default() -> Self {
+        Self::new(70_000_000, 30_000_000)
+    }
+}
+
+impl Planner {
+    /// A planner that may delete any directory, the root included.
+    pub fn new(total_space: u64, required_space: u64) -> Self {
+        Self {
+            total_space,
+            required_space,
+            include_root: true,
+        }
+    }
+
+    /// Whether the root is a candidate for deletion. Deleting the root
+    /// always frees enough space, so with it included [`Self::fewest_dirs`]
+    /// never needs more than one directory.
+    pub fn include_root(self, include_root: bool) -> Self {
+        Self {
+            include_root,
+            ..self
+        }
+    }
+
+    pub fn space_to_free(&self, filesystem: &Filesystem) -> Result<u64, PlanError> {
+        let used_space = filesystem.root().size();
+
+        if used_space > self.total_space {
+            return Err(PlanError::Overfull {
+                used: used_space,
+                total: self.total_space,
+            });
+        }
+
+        if self.required_space > self.total_space {
+            return Err(PlanError::NotEnoughSpace {
+                required: self.required_space,
+                total: self.total_space,
+            });
+        }
+
+        let unused_space = self.total_space - used_space;
+
+        Ok(self.required_space.saturating_sub(unused_space))
+    }
+
+    /// The smallest single candidate directory whose deletion frees enough
+    /// space, or `None` if enough space is already unused. Without the root
+    /// there may be no such directory, which is [`PlanError::Unreachable`].
+    pub fn smallest_dir(&self, filesystem: &Filesystem) -> Result<Option<Deletion>, PlanError> {
+        let space_to_free = self.space_to_free(filesystem)?;
+
+        if space_to_free == 0 {
+            return Ok(None);
+        }
+
+        let candidates = Candidates::new(filesystem, self.include_root);
+
+        candidates
+            .sizes
+            .iter()
+            .enumerate()
+            .filter(|&(_, &size)| size >= space_to_free)
+            .min_by_key(|&(_, &size)| size)
+            .map(|(idx, &size)| {
+                Some(Deletion {
+                    path: filesystem.path(candidates.ids[idx]),
+                    size,
+                })
+            })
+            .ok_or(PlanError::Unreachable {
+                to_free: space_to_free,
+                freeable: candidates.suffix_max[0],
+            })
+    }
+
+    /// The fewest candidate directories, none nested inside another, whose
+    /// deletion frees enough space. Among equally small sets the one freeing
+    /// the least space is chosen.
+    ///
+    /// The search is exhaustive over sets of the minimal size, so its cost
+    /// grows with the number of directories that have to be picked.
+    pub fn fewest_dirs(&self, filesystem: &Filesystem) -> Result<Vec<Deletion>, PlanError> {
+        let space_to_free = self.space_to_free(filesystem)?;
+
+        if space_to_free == 0 {
+            return Ok(vec![]);
+        }
+
+        let candidates = Candidates::new(filesystem, self.include_root);
+
+        // Every candidate lies below one of the top level directories, so
+        // taking the largest of those bounds how few directories can do.
+        let mut top_level = candidates.top_level();
+        top_level.sort_by_key(|&idx| std::cmp::Reverse(candidates.sizes[idx]));
+
+        let mut freed = 0;
+        let mut picks = vec![];
+
+        for idx in top_level {
+            if freed >= space_to_free {
+                break;
+            }
+
+            freed = freed.saturating_add(candidates.sizes[idx]);
+            picks.push(idx);
+        }
+
+        if freed < space_to_free {
+            return Err(PlanError::Unreachable {
+                to_free: space_to_free,
+                freeable: freed,
+            });
+        }
+
+        picks.sort();
+
+        let mut search = Search {
+            candidates: &candidates,
+            space_to_free,
+            chosen: vec![],
+            best: (freed, picks),
+        };
+
+        let picks_left = search.best.1.len();
+        search.run(0, picks_left, 0);
+
+        Ok(search
+            .best
+            .1
+            .into_iter()
+            .map(|idx| Deletion {
+                path: filesystem.path(candidates.ids[idx]),
+                size: candidates.sizes[idx],
+            })
+            .collect())
+    }
+}
+
+/// The directories that may be deleted in pre-order, where the subtree of
+/// the directory at `idx` spans the indices `idx..ends[idx]`.
+struct Candidates {
+    ids: Vec<ItemId>,
+    sizes: Vec<u64>,
+    ends: Vec<usize>,
+    suffix_max: Vec<u64>,
+}
+
+impl Candidates {
+    fn new(filesystem: &Filesystem, include_root: bool) -> Self {
+        let mut ids = vec![];
+        let mut ends = vec![];
+        let mut open = vec![];
+
+        let subdirs = |id| {
+            filesystem
+                .dir(id)
+                .into_iter()
+                .flat_map(|dir| dir.items().iter().rev().copied())
+                .filter(|&id| filesystem.dir(id).is_some())
+        };
+
+        let mut stack: Vec<_> = if include_root {
+            vec![(ItemId::ROOT, 0)]
+        } else {
+            subdirs(ItemId::ROOT).map(|id| (id, 0)).collect()
+        };
+
+        while let Some((id, depth)) = stack.pop() {
+            while open.len() > depth {
+                let idx: usize = open.pop().expect("open directory");
+                ends[idx] = ids.len();
+            }
+
+            open.push(ids.len());
+            ids.push(id);
+            ends.push(0);
+            stack.extend(subdirs(id).map(|id| (id, depth + 1)));
+        }
+
+        for idx in open {
+            ends[idx] = ids.len();
+        }
+
+        let sizes: Vec<u64> = ids.iter().map(|&id| filesystem[id].size()).collect();
+
+        let mut suffix_max = vec![0; sizes.len() + 1];
+
+        for idx in (0..sizes.len()).rev() {
+            suffix_max[idx] = suffix_max[idx + 1].max(sizes[idx]);
+        }
+
+        Self {
+            ids,
+            sizes,
+            ends,
+            suffix_max,
+        }
+    }
+
+    fn top_level(&self) -> Vec<usize> {
+        let mut top_level = vec![];
+        let mut idx = 0;
+
+        while idx < self.ids.len() {
+            top_level.push(idx);
+            idx = self.ends[idx];
+        }
+
+        top_level
+    }
+}
+
+struct Search<'a> {
+    candidates: &'a Candidates,
+    space_to_free: u64,
+    chosen: Vec<usize>,
+    best: (u64, Vec<usize>),
+}
+
+impl<'a> Search<'a> {
+    fn run(&mut self, start: usize, picks_left: usize, freed: u64) {
+        let Candidates {
+            sizes,
+            ends,
+            suffix_max,
+            ..
+        } = self.candidates;
+
+        for idx in start..sizes.len() {
+            let bound = (picks_left as u64).saturating_mul(suffix_max[idx]);
+
+            if freed.saturating_add(bound) < self.space_to_free {
+                break;
+            }
+
+            let freed = freed.saturating_add(sizes[idx]);
+
+            if freed >= self.best.0 {
+                continue;
+            }
+
+            self.chosen.push(idx);
+
+            if freed >= self.space_to_free {
+                self.best = (freed, self.chosen.clone());
+            } else if picks_left > 1 {
+                self.run(ends[idx], picks_left - 1, freed);
+            }
+
+            self.chosen.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::OUTPUT;
+
+    const NESTED: &str = "\
+$ ls
+dir p
+dir s
+$ cd p
+$ ls
+dir q
+dir r
+$ cd q
+$ ls
+7 a
+$ cd ../r
+$ ls
+5 b
+$ cd /s
+$ ls
+6 c
+";
+
+    #[test]
+    fn test_smallest_dir() {
+        let filesystem = OUTPUT.parse().unwrap();
+
+        assert_eq!(
+            Planner::default().smallest_dir(&filesystem),
+            Ok(Some(Deletion {
+                path: "/d".to_owned(),
+                size: 24933642
+            }))
+        );
+
+        assert_eq!(
+            Planner::new(100_000_000, 30_000_000).smallest_dir(&filesystem),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_fewest_dirs() {
+        let filesystem = OUTPUT.parse().unwrap();
+
+        assert_eq!(
+            Planner::default().fewest_dirs(&filesystem),
+            Ok(vec![Deletion {
+                path: "/d".to_owned(),
+                size: 24933642
+            }])
+        );
+
+        let filesystem = NESTED.parse().unwrap();
+
+        assert_eq!(
+            Planner::new(100, 95).fewest_dirs(&filesystem),
+            Ok(vec![Deletion {
+                path: "/".to_owned(),
+                size: 18
+            }])
+        );
+
+        assert_eq!(
+            Planner::new(100, 95)
+                .include_root(false)
+                .fewest_dirs(&filesystem),
+            Ok(vec![
+                Deletion {
+                    path: "/p/q".to_owned(),
+                    size: 7
+                },
+                Deletion {
+                    path: "/s".to_owned(),
+                    size: 6
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let filesystem = NESTED.parse().unwrap();
+
+        assert_eq!(
+            Planner::new(10, 5).smallest_dir(&filesystem),
+            Err(PlanError::Overfull {
+                used: 18,
+                total: 10
+            })
+        );
+
+        assert_eq!(
+            Planner::new(100, 101).fewest_dirs(&filesystem),
+            Err(PlanError::NotEnoughSpace {
+                required: 101,
+                total: 100
+            })
+        );
+
+        let filesystem = OUTPUT.parse().unwrap();
+
+        assert_eq!(
+            Planner::new(48381165, 48381165)
+                .include_root(false)
+                .fewest_dirs(&filesystem),
+            Err(PlanError::Unreachable {
+                to_free: 48381165,
+                freeable: 25028495
+            })
+        );
+    }
+
+    #[test]
+    fn test_include_root() {
+        let filesystem = "$ ls\n10 a\n".parse().unwrap();
+        let root = Deletion {
+            path: "/".to_owned(),
+            size: 10,
+        };
+        let planner = Planner::new(15, 10);
+
+        assert_eq!(planner.smallest_dir(&filesystem), Ok(Some(root.clone())));
+        assert_eq!(planner.fewest_dirs(&filesystem), Ok(vec![root]));
+
+        let planner = planner.include_root(false);
+        let unreachable = PlanError::Unreachable {
+            to_free: 5,
+            freeable: 0,
+        };
+
+        assert_eq!(planner.smallest_dir(&filesystem), Err(unreachable.clone()));
+        assert_eq!(planner.fewest_dirs(&filesystem), Err(unreachable));
+
+        let filesystem = NESTED.parse().unwrap();
+        let planner = Planner::new(100, 93).include_root(false);
+
+        assert_eq!(
+            planner.smallest_dir(&filesystem),
+            Ok(Some(Deletion {
+                path: "/p".to_owned(),
+                size: 12
+            }))
+        );
+        assert_eq!(
+            Planner::new(100, 95)
+                .include_root(false)
+                .smallest_dir(&filesystem),
+            Err(PlanError::Unreachable {
+                to_free: 13,
+                freeable: 12
+            })
+        );
+    }
+
+    #[test]
+    fn test_huge_sizes() {
+        let filesystem = "\
+$ ls
+dir a
+dir b
+$ cd a
+$ ls
+11068046444225730969 x
+$ cd ../b
+$ ls
+7378697629483820646 y
+"
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            Planner::new(u64::MAX, u64::MAX)
+                .include_root(false)
+                .fewest_dirs(&filesystem),
+            Ok(vec![
+                Deletion {
+                    path: "/a".to_owned(),
+                    size: 11068046444225730969
+                },
+                Deletion {
+                    path: "/b".to_owned(),
+                    size: 7378697629483820646
+                }
+            ])
+        );
+    }
+}
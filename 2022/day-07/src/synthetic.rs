@@ -0,0 +1,69 @@
+use crate::{Dir, Filesystem, Item, ItemId};
+
+/// Parameters for a randomly shaped filesystem, e.g. to produce large
+/// terminal transcripts for benchmarking.
+///
+/// Every new item is placed in a uniformly chosen existing directory, so
+/// trees come out bushy near the root with a logarithmic expected depth.
+/// The same seed always yields the same tree.
+#[derive(Clone, Copy, Debug)]
+pub struct Synthetic {
+    pub dirs: usize,
+    pub files: usize,
+    pub max_file_size: u64,
+    pub seed: u64,
+}
+
+impl Synthetic {
+    /// # Panics
+    ///
+    /// If the total size of a directory does not fit in a `u64`.
+    pub fn generate(&self) -> Filesystem {
+        let mut rng = SplitMix64(self.seed);
+        let mut filesystem = Filesystem::empty();
+        let mut dir_ids = vec![ItemId::ROOT];
+        let mut dirs_left = self.dirs;
+        let mut files_left = self.files;
+
+        while dirs_left + files_left > 0 {
+            let idx = dir_ids.len() + self.files - files_left;
+            let parent = dir_ids[rng.below(dir_ids.len() as u64) as usize];
+
+            // Interleave directories and files in proportion to what is left.
+            if rng.below((dirs_left + files_left) as u64) < dirs_left as u64 {
+                let id = filesystem.push(parent, Item::Dir(Dir::new(format!("d{idx}"))));
+                dir_ids.push(id);
+                dirs_left -= 1;
+            } else {
+                let size = 1 + rng.below(self.max_file_size.max(1));
+                let name = format!("f{idx}.dat");
+                filesystem.push(parent, Item::File { name, size });
+                files_left -= 1;
+            }
+        }
+
+        filesystem
+            .compute_sizes()
+            .expect("directory sizes fit in a u64");
+
+        filesystem
+    }
+}
+
+/// A small, fast and reproducible pseudo-random number generator.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
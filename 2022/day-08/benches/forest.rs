@@ -0,0 +1,46 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day_08::{scenic_scores, visibility, Forest};
+
+/// A `size` x `size` grid of pseudo-random digit heights.
+fn forest(size: usize) -> Forest {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut input = String::with_capacity(size * (size + 1));
+
+    for _ in 0..size {
+        for _ in 0..size {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            input.push(char::from(b'0' + (state % 10) as u8));
+        }
+
+        input.push('\n');
+    }
+
+    input.parse().unwrap()
+}
+
+fn bench_forest(c: &mut Criterion) {
+    let mut group = c.benchmark_group("forest");
+
+    for size in [250, 500, 1000] {
+        let forest = forest(size);
+
+        group.throughput(Throughput::Elements((size * size) as u64));
+        group.bench_with_input(
+            BenchmarkId::new("visibility", size),
+            &forest,
+            |b, forest| b.iter(|| visibility(forest)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("scenic_scores", size),
+            &forest,
+            |b, forest| b.iter(|| scenic_scores(forest)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_forest);
+criterion_main!(benches);
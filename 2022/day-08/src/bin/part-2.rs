@@ -5,10 +5,7 @@ use std::fs;
 fn main() -> Result<()> {
     let input = fs::read_to_string("./input.txt")?;
 
-    println!(
-        "{}",
-        part_2(input.lines().map(|l| l.as_bytes().to_vec()).collect())
-    );
+    println!("{}", part_2(input.parse()?));
 
     Ok(())
 }
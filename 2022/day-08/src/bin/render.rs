@@ -0,0 +1,29 @@
+use color_eyre::{eyre::bail, Result};
+use day_08::Heatmap;
+use std::{env, fs, fs::File, io::BufWriter};
+
+/// Usage: `render <visibility|scenic> [image.ppm]`
+///
+/// Prints the heatmap to the terminal, or writes it as a PPM image when a
+/// file name is given.
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let mode = args.next().unwrap_or_else(|| "visibility".to_owned());
+    let image = args.next();
+
+    let input = fs::read_to_string("./input.txt")?;
+    let forest = input.parse()?;
+
+    let heatmap = match mode.as_str() {
+        "visibility" => Heatmap::visibility(&forest),
+        "scenic" => Heatmap::scenic_scores(&forest),
+        _ => bail!("expected 'visibility' or 'scenic', found: {mode:?}"),
+    };
+
+    match image {
+        Some(path) => heatmap.write_ppm(BufWriter::new(File::create(path)?), 8)?,
+        None => print!("{}", heatmap.ansi()),
+    }
+
+    Ok(())
+}
@@ -1,86 +1,154 @@
-use std::ops::BitOr;
+use std::str::FromStr;
+use thiserror::Error;
 
-pub fn part_1(rows: Vec<Vec<u8>>) -> usize {
-    if rows.len() < 3 {
-        return rows.iter().map(|r| r.len()).sum();
+mod render;
+mod sight;
+
+pub use render::{Heatmap, Rgb};
+pub use sight::{Neighbourhood, Sight};
+
+pub fn part_1(forest: Forest) -> usize {
+    visibility(&forest).iter().flatten().filter(|&&v| v).count()
+}
+
+pub fn part_2(forest: Forest) -> usize {
+    scenic_scores(&forest)
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(0)
+}
+
+/// A rectangular grid of tree heights from 0 to 9.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Forest {
+    rows: Vec<Vec<u8>>,
+    width: usize,
+}
+
+impl Forest {
+    pub fn height(&self) -> usize {
+        self.rows.len()
     }
 
-    let mut count = rows[0].len() + rows[rows.len() - 1].len();
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn rows(&self) -> &[Vec<u8>] {
+        &self.rows
+    }
+}
+
+/// A problem with the input grid, with 1-based row and column numbers.
+#[derive(Debug, Error, PartialEq)]
+pub enum ForestError {
+    #[error("invalid height {found:?} at row {row}, column {column}")]
+    InvalidHeight {
+        row: usize,
+        column: usize,
+        found: char,
+    },
+    #[error("row {row} has {found} trees, expected {expected}")]
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+}
 
-    for (i, row) in rows.iter().enumerate().skip(1).take(rows.len() - 2) {
-        if row.len() < 3 {
-            count += row.len();
-            continue;
+impl FromStr for Forest {
+    type Err = ForestError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = s
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(j, c)| match c.to_digit(10) {
+                        Some(d) => Ok(d as u8),
+                        None => Err(ForestError::InvalidHeight {
+                            row: i + 1,
+                            column: j + 1,
+                            found: c,
+                        }),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let width = rows.first().map_or(0, Vec::len);
+
+        if let Some((i, row)) = rows.iter().enumerate().find(|(_, r)| r.len() != width) {
+            return Err(ForestError::RaggedRow {
+                row: i + 1,
+                expected: width,
+                found: row.len(),
+            });
         }
 
-        count += 2;
-
-        for (j, tree) in row.iter().enumerate().skip(1).take(row.len() - 2) {
-            let up: Vec<_> = rows[..i].iter().map(|r| &r[j]).collect();
-            let down: Vec<_> = rows[i + 1..].iter().map(|r| &r[j]).collect();
-            let left: Vec<_> = row[..j].iter().collect();
-            let right: Vec<_> = row[j + 1..].iter().collect();
-
-            if [up, down, left, right]
-                .into_iter()
-                .map(|ts| ts.into_iter().all(|t| t < tree))
-                .reduce(BitOr::bitor)
-                .unwrap_or(false)
-            {
-                count += 1;
+        Ok(Self { rows, width })
+    }
+}
+
+/// Whether each tree can be seen from outside the grid, i.e. whether it is
+/// taller than every tree between it and some edge.
+pub fn visibility(forest: &Forest) -> Vec<Vec<bool>> {
+    let Forest { rows, width } = forest;
+    let (height, width) = (rows.len(), *width);
+    let mut visible = vec![vec![false; width]; height];
+
+    for line in sight::neighbourhood_lines(height, width, Neighbourhood::Four) {
+        let mut tallest = None;
+
+        for (i, j) in line {
+            let tree = rows[i][j];
+
+            if tallest.is_none_or(|t| tree > t) {
+                visible[i][j] = true;
+                tallest = Some(tree);
             }
         }
     }
 
-    count
+    visible
 }
 
-pub fn part_2(rows: Vec<Vec<u8>>) -> usize {
-    rows.iter()
-        .enumerate()
-        .map(|(i, row)| {
-            if i == 0 || i == rows.len() - 1 {
-                return 0;
+/// The scenic score of each tree: the product of its viewing distances up,
+/// down, left and right, each ending at the first tree at least as tall or
+/// at the edge.
+pub fn scenic_scores(forest: &Forest) -> Vec<Vec<usize>> {
+    let Forest { rows, width } = forest;
+    let (height, width) = (rows.len(), *width);
+    let mut scores = vec![vec![1; width]; height];
+
+    for line in sight::neighbourhood_lines(height, width, Neighbourhood::Four) {
+        // Positions along the line whose trees are not hidden behind a taller
+        // one yet, from the nearest edge inwards, with non-increasing heights.
+        let mut blockers: Vec<(usize, u8)> = vec![];
+
+        for (k, (i, j)) in line.into_iter().enumerate() {
+            let tree = rows[i][j];
+
+            while blockers.last().is_some_and(|&(_, b)| b < tree) {
+                blockers.pop();
             }
 
-            row.iter()
-                .enumerate()
-                .map(|(j, tree)| {
-                    if j == 0 || j == row.len() - 1 {
-                        return 0;
-                    }
-
-                    let up: Vec<_> = rows[..i].iter().rev().map(|r| &r[j]).collect();
-                    let down: Vec<_> = rows[i + 1..].iter().map(|r| &r[j]).collect();
-                    let left: Vec<_> = row[..j].iter().rev().collect();
-                    let right: Vec<_> = row[j + 1..].iter().collect();
-
-                    [up, down, left, right]
-                        .into_iter()
-                        .map(|ts| {
-                            let len = ts.len();
-                            let smaller = ts.into_iter().take_while(|&t| t < tree).count();
-
-                            if smaller < len {
-                                smaller + 1
-                            } else {
-                                len
-                            }
-                        })
-                        .product()
-                })
-                .max()
-                .unwrap_or(0)
-        })
-        .max()
-        .unwrap_or(0)
+            scores[i][j] *= blockers.last().map_or(k, |&(b, _)| k - b);
+            blockers.push((k, tree));
+        }
+    }
+
+    scores
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const TREES: &str = "\
+    pub(crate) const TREES: &str = "\
 30373
 25512
 65332
@@ -98,7 +166,7 @@ mod tests {
             ("444\n444\n444", 8),
             (TREES, 21),
         ] {
-            let answer = part_1(i.lines().map(|l| l.as_bytes().to_vec()).collect());
+            let answer = part_1(i.parse().unwrap());
 
             assert_eq!(answer, o);
         }
@@ -121,9 +189,62 @@ mod tests {
             ),
             (TREES, 8),
         ] {
-            let answer = part_2(i.lines().map(|l| l.as_bytes().to_vec()).collect());
+            let answer = part_2(i.parse().unwrap());
 
             assert_eq!(answer, o);
         }
     }
+
+    #[test]
+    fn test_parse_forest() {
+        let forest = "012\n345".parse::<Forest>().unwrap();
+
+        assert_eq!(forest.rows(), [vec![0, 1, 2], vec![3, 4, 5]]);
+        assert_eq!((forest.height(), forest.width()), (2, 3));
+
+        assert_eq!(
+            "012\n3a5".parse::<Forest>(),
+            Err(ForestError::InvalidHeight {
+                row: 2,
+                column: 2,
+                found: 'a'
+            })
+        );
+
+        assert_eq!(
+            "012\n34\n567".parse::<Forest>(),
+            Err(ForestError::RaggedRow {
+                row: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_visibility() {
+        let forest = TREES.parse().unwrap();
+
+        assert_eq!(
+            visibility(&forest),
+            ["TTTTT", "TTTFT", "TTFTT", "TFTFT", "TTTTT"]
+                .map(|l| l.chars().map(|c| c == 'T').collect::<Vec<_>>())
+        );
+    }
+
+    #[test]
+    fn test_scenic_scores() {
+        let forest = TREES.parse().unwrap();
+
+        assert_eq!(
+            scenic_scores(&forest),
+            [
+                [0, 0, 0, 0, 0],
+                [0, 1, 4, 1, 0],
+                [0, 6, 1, 2, 0],
+                [0, 1, 8, 3, 0],
+                [0, 0, 0, 0, 0],
+            ]
+        );
+    }
 }
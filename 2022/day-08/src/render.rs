@@ -0,0 +1,196 @@
+use crate::{scenic_scores, visibility, Forest};
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    fn lerp(self, other: Self, t: f64) -> Self {
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+
+        Self(
+            mix(self.0, other.0),
+            mix(self.1, other.1),
+            mix(self.2, other.2),
+        )
+    }
+
+    /// Picks black or white, whichever reads better on this background.
+    fn contrast(self) -> Self {
+        let luma = 0.299 * self.0 as f64 + 0.587 * self.1 as f64 + 0.114 * self.2 as f64;
+
+        if luma > 128.0 {
+            Self(0, 0, 0)
+        } else {
+            Self(255, 255, 255)
+        }
+    }
+}
+
+/// A colour per tree, drawn over the tree heights.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heatmap {
+    width: usize,
+    height: usize,
+    heights: Vec<u8>,
+    colours: Vec<Rgb>,
+}
+
+impl Heatmap {
+    fn new(forest: &Forest, colour: impl Fn(usize, usize) -> Rgb) -> Self {
+        let mut heights = vec![];
+        let mut colours = vec![];
+
+        for (i, row) in forest.rows().iter().enumerate() {
+            for (j, &height) in row.iter().enumerate() {
+                heights.push(height);
+                colours.push(colour(i, j));
+            }
+        }
+
+        Self {
+            width: forest.width(),
+            height: forest.height(),
+            heights,
+            colours,
+        }
+    }
+
+    /// Visible trees in shades of green by height, hidden ones in grey.
+    pub fn visibility(forest: &Forest) -> Self {
+        let visible = visibility(forest);
+        let rows = forest.rows();
+
+        Self::new(forest, |i, j| {
+            let t = rows[i][j] as f64 / 9.0;
+
+            if visible[i][j] {
+                Rgb(20, 90, 30).lerp(Rgb(120, 230, 110), t)
+            } else {
+                Rgb(40, 40, 40).lerp(Rgb(110, 110, 110), t)
+            }
+        })
+    }
+
+    /// Scenic scores on a logarithmic scale from dark blue through magenta
+    /// to yellow for the best spot.
+    pub fn scenic_scores(forest: &Forest) -> Self {
+        let scores = scenic_scores(forest);
+        let max = scores.iter().flatten().copied().max().unwrap_or(0);
+        let scale = (max as f64).ln_1p();
+
+        Self::new(forest, |i, j| {
+            let t = if max == 0 {
+                0.0
+            } else {
+                (scores[i][j] as f64).ln_1p() / scale
+            };
+
+            if t < 0.5 {
+                Rgb(15, 10, 60).lerp(Rgb(190, 50, 130), t * 2.0)
+            } else {
+                Rgb(190, 50, 130).lerp(Rgb(250, 230, 80), t * 2.0 - 1.0)
+            }
+        })
+    }
+
+    /// Draws every tree height on its colour using 24-bit ANSI escapes.
+    pub fn ansi(&self) -> String {
+        let mut out = String::new();
+
+        for (row_heights, row_colours) in self
+            .heights
+            .chunks(self.width.max(1))
+            .zip(self.colours.chunks(self.width.max(1)))
+        {
+            for (height, &colour) in row_heights.iter().zip(row_colours) {
+                let Rgb(r, g, b) = colour;
+                let Rgb(fr, fg, fb) = colour.contrast();
+
+                out.push_str(&format!(
+                    "\x1b[48;2;{r};{g};{b}m\x1b[38;2;{fr};{fg};{fb}m{height}"
+                ));
+            }
+
+            out.push_str("\x1b[0m\n");
+        }
+
+        out
+    }
+
+    /// Writes a binary PPM image with each tree as a `scale` x `scale`
+    /// square, flushing `writer` so that buffered errors are not lost.
+    pub fn write_ppm(&self, mut writer: impl Write, scale: usize) -> io::Result<()> {
+        write!(
+            writer,
+            "P6\n{} {}\n255\n",
+            self.width * scale,
+            self.height * scale
+        )?;
+
+        for row in self.colours.chunks(self.width.max(1)) {
+            let line: Vec<u8> = row
+                .iter()
+                .flat_map(|&Rgb(r, g, b)| [r, g, b].repeat(scale))
+                .collect();
+
+            for _ in 0..scale {
+                writer.write_all(&line)?;
+            }
+        }
+
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ansi() {
+        let forest = "19\n11".parse().unwrap();
+        let ansi = Heatmap::visibility(&forest).ansi();
+
+        assert_eq!(ansi.lines().count(), 2);
+        assert_eq!(ansi.matches('m').count(), 2 * 4 + 2);
+        assert!(ansi.starts_with("\x1b[48;2;31;106;39m\x1b[38;2;255;255;255m1"));
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let forest = "012\n345".parse().unwrap();
+        let mut ppm = vec![];
+
+        Heatmap::scenic_scores(&forest)
+            .write_ppm(&mut ppm, 2)
+            .unwrap();
+
+        let header = b"P6\n6 4\n255\n";
+
+        assert!(ppm.starts_with(header));
+        assert_eq!(ppm.len(), header.len() + 6 * 4 * 3);
+        assert_eq!(ppm[header.len()..][..3], [15, 10, 60]);
+    }
+
+    #[test]
+    fn test_write_ppm_flush_error() {
+        struct FailingFlush;
+
+        impl Write for FailingFlush {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Err(io::Error::other("disk full"))
+            }
+        }
+
+        let forest = "01\n23".parse().unwrap();
+
+        assert!(Heatmap::visibility(&forest)
+            .write_ppm(FailingFlush, 1)
+            .is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use crate::Forest;
+
+/// The directions a tree is looked at from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Neighbourhood {
+    /// Up, down, left and right.
+    Four,
+    /// The four directions plus the diagonals.
+    Eight,
+}
+
+impl Neighbourhood {
+    /// Each direction as a `(row, column)` step.
+    pub fn directions(self) -> &'static [(isize, isize)] {
+        const EIGHT: [(isize, isize); 8] = [
+            (-1, 0),
+            (1, 0),
+            (0, -1),
+            (0, 1),
+            (-1, -1),
+            (-1, 1),
+            (1, -1),
+            (1, 1),
+        ];
+
+        match self {
+            Self::Four => &EIGHT[..4],
+            Self::Eight => &EIGHT,
+        }
+    }
+}
+
+/// Every straight line through a `height` x `width` grid stepping by
+/// `(di, dj)`, as `(row, column)` positions in walking order.
+pub(crate) fn lines(
+    height: usize,
+    width: usize,
+    (di, dj): (isize, isize),
+) -> impl Iterator<Item = Vec<(usize, usize)>> {
+    let inside = move |i: isize, j: isize| {
+        (0..height as isize).contains(&i) && (0..width as isize).contains(&j)
+    };
+
+    // A line starts wherever stepping backwards would leave the grid.
+    (0..height as isize)
+        .flat_map(move |i| (0..width as isize).map(move |j| (i, j)))
+        .filter(move |&(i, j)| !inside(i - di, j - dj))
+        .map(move |(mut i, mut j)| {
+            let mut line = vec![];
+
+            while inside(i, j) {
+                line.push((i as usize, j as usize));
+                i += di;
+                j += dj;
+            }
+
+            line
+        })
+}
+
+/// The lines along every direction of a neighbourhood.
+pub(crate) fn neighbourhood_lines(
+    height: usize,
+    width: usize,
+    neighbourhood: Neighbourhood,
+) -> impl Iterator<Item = Vec<(usize, usize)>> {
+    neighbourhood
+        .directions()
+        .iter()
+        .flat_map(move |&direction| lines(height, width, direction))
+}
+
+/// Sight line queries over a configurable set of directions, where
+/// `blocks(viewer, other)` tells whether a tree of height `other` cuts off
+/// the view of a tree of height `viewer`.
+///
+/// Heights only range from 0 to 9, so every sweep keeps the last position of
+/// each height and stays linear whatever the predicate.
+pub struct Sight {
+    neighbourhood: Neighbourhood,
+    blocks: [[bool; 10]; 10],
+}
+
+impl Sight {
+    pub fn new(neighbourhood: Neighbourhood, blocks: impl Fn(u8, u8) -> bool) -> Self {
+        let mut table = [[false; 10]; 10];
+
+        for (viewer, row) in table.iter_mut().enumerate() {
+            for (other, cell) in row.iter_mut().enumerate() {
+                *cell = blocks(viewer as u8, other as u8);
+            }
+        }
+
+        Self {
+            neighbourhood,
+            blocks: table,
+        }
+    }
+
+    /// Whether each tree can be seen from outside the grid along at least
+    /// one direction.
+    pub fn visibility(&self, forest: &Forest) -> Vec<Vec<bool>> {
+        let rows = forest.rows();
+        let mut visible = vec![vec![false; forest.width()]; forest.height()];
+
+        for line in neighbourhood_lines(forest.height(), forest.width(), self.neighbourhood) {
+            let mut seen = [false; 10];
+
+            for (i, j) in line {
+                let tree = rows[i][j] as usize;
+
+                if !(0..10).any(|other| seen[other] && self.blocks[tree][other]) {
+                    visible[i][j] = true;
+                }
+
+                seen[tree] = true;
+            }
+        }
+
+        visible
+    }
+
+    /// The product of each tree's viewing distances along every direction,
+    /// each ending at the first blocking tree or at the edge.
+    pub fn scenic_scores(&self, forest: &Forest) -> Vec<Vec<usize>> {
+        let rows = forest.rows();
+        let mut scores = vec![vec![1; forest.width()]; forest.height()];
+
+        for line in neighbourhood_lines(forest.height(), forest.width(), self.neighbourhood) {
+            let mut last_seen: [Option<usize>; 10] = [None; 10];
+
+            for (k, (i, j)) in line.into_iter().enumerate() {
+                let tree = rows[i][j] as usize;
+
+                let blocker = (0..10)
+                    .filter(|&other| self.blocks[tree][other])
+                    .filter_map(|other| last_seen[other])
+                    .max();
+
+                scores[i][j] *= blocker.map_or(k, |b| k - b);
+                last_seen[tree] = Some(k);
+            }
+        }
+
+        scores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{scenic_scores, tests::TREES, visibility};
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(
+            lines(2, 3, (1, 1)).collect::<Vec<_>>(),
+            vec![
+                vec![(0, 0), (1, 1)],
+                vec![(0, 1), (1, 2)],
+                vec![(0, 2)],
+                vec![(1, 0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_four_matches_puzzle() {
+        let forest = TREES.parse().unwrap();
+        let sight = Sight::new(Neighbourhood::Four, |viewer, other| other >= viewer);
+
+        assert_eq!(sight.visibility(&forest), visibility(&forest));
+        assert_eq!(sight.scenic_scores(&forest), scenic_scores(&forest));
+    }
+
+    #[test]
+    fn test_variations() {
+        let forest = TREES.parse().unwrap();
+
+        for (neighbourhood, strict, visible, best) in [
+            (Neighbourhood::Four, true, 23, 12),
+            (Neighbourhood::Eight, false, 22, 16),
+            (Neighbourhood::Eight, true, 24, 48),
+        ] {
+            let sight = Sight::new(neighbourhood, |viewer, other| {
+                other > viewer || !strict && other == viewer
+            });
+
+            let count = sight
+                .visibility(&forest)
+                .iter()
+                .flatten()
+                .filter(|&&v| v)
+                .count();
+            let max = sight.scenic_scores(&forest).into_iter().flatten().max();
+
+            assert_eq!((count, max), (visible, Some(best)));
+        }
+    }
+
+    #[test]
+    fn test_eight_scenic_scores() {
+        let forest = TREES.parse().unwrap();
+        let sight = Sight::new(Neighbourhood::Eight, |viewer, other| other >= viewer);
+
+        assert_eq!(
+            sight.scenic_scores(&forest),
+            [
+                [0, 0, 0, 0, 0],
+                [0, 3, 8, 1, 0],
+                [0, 6, 2, 2, 0],
+                [0, 1, 16, 6, 0],
+                [0, 0, 0, 0, 0],
+            ]
+        );
+    }
+}